@@ -0,0 +1,154 @@
+//! Lookups in the system user and group databases (Unix only)
+
+use std::ffi::{CStr, CString};
+
+/// Initial buffer size for the reentrant lookup functions
+const LOOKUP_BUFFER_SIZE: usize = 4096;
+
+/// Largest buffer tried before giving up on ERANGE
+const MAX_LOOKUP_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Primary group and home are only used by command checks
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "command-checks"), allow(dead_code))]
+pub struct UserEntry {
+    pub name: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub home: String,
+}
+
+/// Look up a user by name, or by numeric uid if `name` is a number
+pub fn lookup_user(name: &str) -> Result<Option<UserEntry>, String> {
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER_SIZE];
+
+    loop {
+        let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+        let rc = match name.parse::<u32>() {
+            Ok(uid) => unsafe {
+                libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result)
+            },
+            Err(_) => {
+                let c_name =
+                    CString::new(name).map_err(|_| format!("Invalid user name: {}", name))?;
+                unsafe {
+                    libc::getpwnam_r(
+                        c_name.as_ptr(),
+                        &mut pwd,
+                        buf.as_mut_ptr(),
+                        buf.len(),
+                        &mut result,
+                    )
+                }
+            }
+        };
+
+        if rc == libc::ERANGE && buf.len() < MAX_LOOKUP_BUFFER_SIZE {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if rc != 0 {
+            return Err(format!(
+                "Failed to look up user {}: {}",
+                name,
+                std::io::Error::from_raw_os_error(rc)
+            ));
+        }
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        return Ok(Some(UserEntry {
+            name: unsafe { c_string(pwd.pw_name) },
+            uid: pwd.pw_uid,
+            gid: pwd.pw_gid,
+            home: unsafe { c_string(pwd.pw_dir) },
+        }));
+    }
+}
+
+/// Look up a group id by name, or parse `name` if it is a number
+pub fn lookup_group(name: &str) -> Result<Option<u32>, String> {
+    if let Ok(gid) = name.parse::<u32>() {
+        return Ok(Some(gid));
+    }
+
+    let c_name = CString::new(name).map_err(|_| format!("Invalid group name: {}", name))?;
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER_SIZE];
+
+    loop {
+        let mut grp: libc::group = unsafe { std::mem::zeroed() };
+        let mut result: *mut libc::group = std::ptr::null_mut();
+
+        let rc = unsafe {
+            libc::getgrnam_r(
+                c_name.as_ptr(),
+                &mut grp,
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut result,
+            )
+        };
+
+        if rc == libc::ERANGE && buf.len() < MAX_LOOKUP_BUFFER_SIZE {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if rc != 0 {
+            return Err(format!(
+                "Failed to look up group {}: {}",
+                name,
+                std::io::Error::from_raw_os_error(rc)
+            ));
+        }
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        return Ok(Some(grp.gr_gid));
+    }
+}
+
+/// Name of the group with id `gid`, if it exists
+pub fn group_name(gid: u32) -> Result<Option<String>, String> {
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER_SIZE];
+
+    loop {
+        let mut grp: libc::group = unsafe { std::mem::zeroed() };
+        let mut result: *mut libc::group = std::ptr::null_mut();
+
+        let rc =
+            unsafe { libc::getgrgid_r(gid, &mut grp, buf.as_mut_ptr(), buf.len(), &mut result) };
+
+        if rc == libc::ERANGE && buf.len() < MAX_LOOKUP_BUFFER_SIZE {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if rc != 0 {
+            return Err(format!(
+                "Failed to look up group {}: {}",
+                gid,
+                std::io::Error::from_raw_os_error(rc)
+            ));
+        }
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        return Ok(Some(unsafe { c_string(grp.gr_name) }));
+    }
+}
+
+/// Copy a C string owned by a lookup buffer
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string.
+unsafe fn c_string(ptr: *const libc::c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
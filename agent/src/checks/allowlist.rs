@@ -0,0 +1,55 @@
+//! Local allowlist of commands the agent is willing to run
+
+use std::collections::HashSet;
+
+use anyhow::Context;
+use regex::Regex;
+
+/// Commands permitted for command checks, read from a file on the endpoint.
+///
+/// One entry per line. Lines are matched exactly against the whole command;
+/// lines starting with `regex:` are patterns that must match the whole
+/// command. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct CommandAllowlist {
+    exact: HashSet<String>,
+    patterns: Vec<Regex>,
+}
+
+impl CommandAllowlist {
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read command allowlist {}", path))?;
+
+        Self::parse(&content).with_context(|| format!("Invalid command allowlist {}", path))
+    }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let mut allowlist = Self::default();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.strip_prefix("regex:") {
+                Some(pattern) => {
+                    let regex = Regex::new(&format!("^(?:{})$", pattern.trim()))
+                        .with_context(|| format!("line {}", number + 1))?;
+                    allowlist.patterns.push(regex);
+                }
+                None => {
+                    allowlist.exact.insert(line.to_string());
+                }
+            }
+        }
+
+        Ok(allowlist)
+    }
+
+    pub fn allows(&self, command: &str) -> bool {
+        let command = command.trim();
+        self.exact.contains(command) || self.patterns.iter().any(|p| p.is_match(command))
+    }
+}
@@ -0,0 +1,164 @@
+use common::{CheckTypeId, CommandOutputParams};
+use regex::Regex;
+use serde_json::json;
+
+use crate::checks::allowlist::CommandAllowlist;
+use crate::checks::evidence::text_excerpt;
+use crate::checks::runner::{run_shell_command, CommandRun};
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+/// Characters of output included in failure messages
+const OUTPUT_EXCERPT_CHARS: usize = 200;
+
+pub struct CommandOutput {
+    allow_root: bool,
+    allowlist: Option<CommandAllowlist>,
+}
+
+impl CommandOutput {
+    pub fn new(allow_root: bool, allowlist: Option<CommandAllowlist>) -> Self {
+        Self {
+            allow_root,
+            allowlist,
+        }
+    }
+}
+
+impl Check for CommandOutput {
+    type Params = CommandOutputParams;
+    const TYPE: CheckTypeId = CheckTypeId::CommandOutput;
+
+    /// Allowlisted commands run unsigned only with the default environment,
+    /// directory and identity; anything else must be signed
+    fn requires_signature(&self, params: &CommandOutputParams) -> bool {
+        let customized = !params.env.is_empty()
+            || !params.pass_env.is_empty()
+            || params.working_dir.is_some()
+            || params.run_as_user.is_some()
+            || params.run_as_group.is_some();
+
+        customized
+            || !self
+                .allowlist
+                .as_ref()
+                .is_some_and(|allowlist| allowlist.allows(&params.command))
+    }
+
+    fn execute(&self, params: &CommandOutputParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let run = match run_shell_command(params, self.allow_root) {
+            Ok(run) => run,
+            Err(e) => return CheckExecutionResult::error(e),
+        };
+
+        let evidence = json!({
+            "command": params.command,
+            "exit_code": run.exit_code,
+            "stdout": text_excerpt(&run.stdout),
+            "stderr": text_excerpt(&run.stderr),
+            "truncated": run.truncated,
+            "expected_exit_code": params.expected_exit_code,
+            "expected_pattern": params.expected_pattern,
+        });
+
+        let result = evaluate(params, &run).with_evidence(evidence);
+
+        // Commands that print a single number report it as the measured value
+        match run.stdout.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => result.with_value(value),
+            _ => result,
+        }
+    }
+}
+
+/// Compare a finished command against the expected exit code or pattern
+fn evaluate(params: &CommandOutputParams, run: &CommandRun) -> CheckExecutionResult {
+    let combined = run.combined_output();
+    let excerpt: String = combined.chars().take(OUTPUT_EXCERPT_CHARS).collect();
+    let truncated = if run.truncated {
+        " (output truncated)"
+    } else {
+        ""
+    };
+
+    if let Some(expected) = params.expected_exit_code {
+        return match run.exit_code {
+            Some(code) if code == expected => CheckExecutionResult::pass(Some(format!(
+                "Command exited with expected code {}",
+                code
+            ))),
+            Some(code) => CheckExecutionResult::fail(format!(
+                "Command exited with code {} (expected {}). Output: {}",
+                code, expected, excerpt
+            )),
+            None => CheckExecutionResult::fail(format!(
+                "Command was terminated by a signal (expected exit code {})",
+                expected
+            )),
+        };
+    }
+
+    let pattern = params.expected_pattern.as_deref().unwrap_or_default();
+    let regex = match Regex::new(pattern) {
+        Ok(r) => r,
+        Err(e) => return CheckExecutionResult::error(format!("Invalid regex pattern: {}", e)),
+    };
+
+    if regex.is_match(&combined) {
+        CheckExecutionResult::pass(Some(format!(
+            "Command output matches expected pattern{}",
+            truncated
+        )))
+    } else {
+        CheckExecutionResult::fail(format!(
+            "Command output does not match pattern{}. Output: {}",
+            truncated, excerpt
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{AgentCheckDefinition, CheckStatus, Severity};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::checks::CheckRegistry;
+
+    fn registry() -> CheckRegistry {
+        let allowlist = CommandAllowlist::parse("echo ok\n").unwrap();
+        let mut registry = CheckRegistry::new();
+        registry.register(CommandOutput::new(true, Some(allowlist)));
+        registry
+    }
+
+    fn definition(parameters: serde_json::Value) -> AgentCheckDefinition {
+        AgentCheckDefinition {
+            id: Uuid::new_v4(),
+            name: "command".to_string(),
+            check_type: "command_output".to_string(),
+            parameters,
+            severity: Severity::Medium,
+            schedule: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn allowlisted_command_runs_unsigned() {
+        let check = definition(json!({"command": "echo ok", "expected_pattern": "ok"}));
+        let result = registry().execute(&check, &CheckContext::new());
+        assert_eq!(result.status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn allowlisted_command_with_env_requires_signature() {
+        let check = definition(json!({
+            "command": "echo ok",
+            "expected_pattern": "ok",
+            "env": {"LD_PRELOAD": "/tmp/evil.so"}
+        }));
+        let result = registry().execute(&check, &CheckContext::new());
+        assert_eq!(result.status, CheckStatus::Error);
+    }
+}
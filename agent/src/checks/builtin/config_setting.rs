@@ -0,0 +1,78 @@
+use std::fs;
+
+use common::{CheckTypeId, ConfigSettingParams};
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::checks::compare::{compare, expectation};
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+pub struct ConfigSetting;
+
+impl Check for ConfigSetting {
+    type Params = ConfigSettingParams;
+    const TYPE: CheckTypeId = CheckTypeId::ConfigSetting;
+
+    fn execute(&self, params: &ConfigSettingParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let content = match fs::read_to_string(&params.file) {
+            Ok(c) => c,
+            Err(e) => {
+                return CheckExecutionResult::error(format!(
+                    "Failed to read config file {}: {}",
+                    params.file, e
+                ))
+            }
+        };
+
+        // Try different config file formats
+
+        // INI-style: key=value or key = value, or whitespace separated as in login.defs
+        let pattern = format!(
+            r"(?m)^\s*{}(?:\s*[=:]\s*|\s+)(.*)$",
+            regex::escape(&params.key)
+        );
+        let observed = Regex::new(&pattern).ok().and_then(|re| {
+            re.captures(&content).map(|caps| {
+                caps.get(1)
+                    .map(|m| m.as_str().trim())
+                    .unwrap_or("")
+                    .to_string()
+            })
+        });
+        let evidence = json!({
+            "file": params.file,
+            "key": params.key,
+            "operator": params.operator,
+            "expected": params.expected,
+            "observed": observed,
+        });
+
+        let expected = expectation(params.operator, params.expected.as_ref());
+        let result = match compare(
+            params.operator,
+            observed.clone().map(Value::String).as_ref(),
+            params.expected.as_ref(),
+            params.if_missing,
+        ) {
+            Ok(true) => CheckExecutionResult::pass(Some(match &observed {
+                Some(value) => format!(
+                    "Config setting matches: {} = {} ({})",
+                    params.key, value, expected
+                ),
+                None => format!("Config setting not set: {} in {}", params.key, params.file),
+            })),
+            Ok(false) => CheckExecutionResult::fail(match &observed {
+                Some(value) => format!(
+                    "Config setting mismatch: {} = {} (expected {})",
+                    params.key, value, expected
+                ),
+                None => format!(
+                    "Config setting not found: {} in {}",
+                    params.key, params.file
+                ),
+            }),
+            Err(e) => CheckExecutionResult::error(format!("Config setting {}: {}", params.key, e)),
+        };
+        result.with_evidence(evidence)
+    }
+}
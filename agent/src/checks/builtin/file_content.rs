@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::Path;
+
+use common::{CheckTypeId, FileContentParams};
+use regex::Regex;
+use serde_json::json;
+
+use crate::checks::evidence::{file_metadata, matched_lines};
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+pub struct FileContent;
+
+impl Check for FileContent {
+    type Params = FileContentParams;
+    const TYPE: CheckTypeId = CheckTypeId::FileContent;
+
+    fn execute(&self, params: &FileContentParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let content = match fs::read_to_string(&params.path) {
+            Ok(c) => c,
+            Err(e) => {
+                return CheckExecutionResult::error(format!(
+                    "Failed to read file {}: {}",
+                    params.path, e
+                ))
+            }
+        };
+
+        let regex = match Regex::new(&params.pattern) {
+            Ok(r) => r,
+            Err(e) => return CheckExecutionResult::error(format!("Invalid regex pattern: {}", e)),
+        };
+
+        let lines = matched_lines(&content, &regex);
+        let matches = !lines.is_empty();
+        let evidence = json!({
+            "path": params.path,
+            "pattern": params.pattern,
+            "should_match": params.should_match,
+            "matched": matches,
+            "matched_lines": lines,
+            "metadata": file_metadata(Path::new(&params.path)),
+        });
+
+        let result = if matches == params.should_match {
+            CheckExecutionResult::pass(Some(format!(
+                "Pattern {} in file",
+                if matches { "found" } else { "not found" }
+            )))
+        } else {
+            CheckExecutionResult::fail(format!(
+                "Pattern {} in file (expected {})",
+                if matches { "found" } else { "not found" },
+                if params.should_match {
+                    "match"
+                } else {
+                    "no match"
+                }
+            ))
+        };
+        result.with_evidence(evidence)
+    }
+}
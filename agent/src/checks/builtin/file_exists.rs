@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use common::{CheckTypeId, FileExistsParams};
+use serde_json::json;
+
+use crate::checks::evidence::file_metadata;
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+pub struct FileExists;
+
+impl Check for FileExists {
+    type Params = FileExistsParams;
+    const TYPE: CheckTypeId = CheckTypeId::FileExists;
+
+    fn execute(&self, params: &FileExistsParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let path = Path::new(&params.path);
+        let exists = path.exists();
+        let evidence = json!({
+            "path": params.path,
+            "exists": exists,
+            "metadata": file_metadata(path),
+        });
+
+        let result = if exists {
+            CheckExecutionResult::pass(Some(format!("File exists: {}", params.path)))
+        } else {
+            CheckExecutionResult::fail(format!("File not found: {}", params.path))
+        };
+        result.with_evidence(evidence)
+    }
+}
@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use common::{CheckTypeId, FileHashParams, HashAlgorithm};
+use serde_json::json;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::checks::evidence::EVIDENCE_MAX_LINES;
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+/// Most files a single check hashes, so a broad glob cannot stall a cycle
+const MAX_HASHED_FILES: usize = 1000;
+
+pub struct FileHash;
+
+impl Check for FileHash {
+    type Params = FileHashParams;
+    const TYPE: CheckTypeId = CheckTypeId::FileHash;
+
+    fn execute(&self, params: &FileHashParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let files = match matching_files(&params.path) {
+            Ok(files) => files,
+            Err(e) => return CheckExecutionResult::error(e),
+        };
+        if files.is_empty() {
+            return CheckExecutionResult::fail(format!("No files match {}", params.path))
+                .with_evidence(json!({ "path": params.path, "files": [] }));
+        }
+        let mut hashed = Vec::new();
+        let mut mismatched = Vec::new();
+        for file in &files {
+            let hash = match hash_file(file, params.algorithm) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    return CheckExecutionResult::error(format!(
+                        "Failed to hash {}: {}",
+                        file.display(),
+                        e
+                    ))
+                }
+            };
+            let matches = params
+                .expected
+                .iter()
+                .any(|expected| expected.eq_ignore_ascii_case(&hash));
+            if !matches {
+                mismatched.push(file.display().to_string());
+            }
+            if hashed.len() < EVIDENCE_MAX_LINES || !matches {
+                hashed.push(json!({
+                    "path": file.display().to_string(),
+                    "hash": hash,
+                    "matches": matches,
+                }));
+            }
+        }
+
+        let evidence = json!({
+            "path": params.path,
+            "algorithm": params.algorithm,
+            "expected": params.expected,
+            "file_count": files.len(),
+            "files": hashed,
+        });
+
+        let result = if mismatched.is_empty() {
+            CheckExecutionResult::pass(Some(format!(
+                "{} file(s) match the expected {} hash",
+                files.len(),
+                params.algorithm
+            )))
+        } else {
+            CheckExecutionResult::fail(format!(
+                "{} of {} file(s) do not match the expected {} hash: {}",
+                mismatched.len(),
+                files.len(),
+                params.algorithm,
+                mismatched.join(", ")
+            ))
+        };
+        result.with_evidence(evidence)
+    }
+}
+
+/// Regular files matched by `path`, which may be a glob.
+///
+/// Expansion stops with an error as soon as more than `MAX_HASHED_FILES`
+/// files match.
+fn matching_files(path: &str) -> Result<Vec<PathBuf>, String> {
+    if !path.contains(['*', '?', '[']) {
+        let path = PathBuf::from(path);
+        return Ok(if path.is_file() {
+            vec![path]
+        } else {
+            Vec::new()
+        });
+    }
+
+    let paths = glob::glob(path).map_err(|e| format!("Invalid glob {}: {}", path, e))?;
+    let mut files = Vec::new();
+    for entry in paths {
+        match entry {
+            Ok(file) if file.is_file() => {
+                if files.len() == MAX_HASHED_FILES {
+                    return Err(format!(
+                        "{} matches more than {} files, the most a check can hash",
+                        path, MAX_HASHED_FILES
+                    ));
+                }
+                files.push(file);
+            }
+            Ok(_) => {}
+            // Unreadable directories would hide files from the check
+            Err(e) => return Err(format!("Failed to expand {}: {}", path, e)),
+        }
+    }
+    Ok(files)
+}
+
+fn hash_file(path: &Path, algorithm: HashAlgorithm) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    Ok(match algorithm {
+        HashAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            io::copy(&mut file, &mut hasher)?;
+            format!("{:x}", hasher.finalize())
+        }
+        HashAlgorithm::Sha512 => {
+            let mut hasher = Sha512::new();
+            io::copy(&mut file, &mut hasher)?;
+            format!("{:x}", hasher.finalize())
+        }
+    })
+}
@@ -0,0 +1,168 @@
+use common::{CheckTypeId, FilePermissionsParams};
+
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+pub struct FilePermissions;
+
+impl Check for FilePermissions {
+    type Params = FilePermissionsParams;
+    const TYPE: CheckTypeId = CheckTypeId::FilePermissions;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux, Platform::MacOs]
+    }
+
+    #[cfg(unix)]
+    fn execute(&self, params: &FilePermissionsParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        use std::os::unix::fs::MetadataExt;
+
+        use common::{parse_octal_mode, FileKind};
+        use serde_json::json;
+
+        use crate::checks::accounts::{group_name, lookup_group, lookup_user};
+
+        // Only an expected symlink is inspected without following the link
+        let metadata = if params.file_type == Some(FileKind::Symlink) {
+            std::fs::symlink_metadata(&params.path)
+        } else {
+            std::fs::metadata(&params.path)
+        };
+        let metadata = match metadata {
+            Ok(m) => m,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return CheckExecutionResult::fail(format!("File not found: {}", params.path))
+                    .with_evidence(json!({ "path": params.path, "exists": false }))
+            }
+            Err(e) => {
+                return CheckExecutionResult::error(format!(
+                    "Failed to read metadata of {}: {}",
+                    params.path, e
+                ))
+            }
+        };
+
+        let mode = metadata.mode() & 0o7777;
+        let file_type = metadata.file_type();
+        let kind = if file_type.is_symlink() {
+            Some(FileKind::Symlink)
+        } else if file_type.is_dir() {
+            Some(FileKind::Directory)
+        } else if file_type.is_file() {
+            Some(FileKind::File)
+        } else {
+            None
+        };
+        let owner = lookup_user(&metadata.uid().to_string())
+            .ok()
+            .flatten()
+            .map(|u| u.name);
+        let group = group_name(metadata.gid()).ok().flatten();
+
+        let evidence = json!({
+            "path": params.path,
+            "exists": true,
+            "observed": {
+                "mode": format!("{:04o}", mode),
+                "uid": metadata.uid(),
+                "owner": owner,
+                "gid": metadata.gid(),
+                "group": group,
+                "file_type": kind,
+            },
+            "expected": {
+                "mode": params.mode,
+                "max_mode": params.max_mode,
+                "owner": params.owner,
+                "group": params.group,
+                "file_type": params.file_type,
+            },
+        });
+
+        let mut mismatches = Vec::new();
+
+        if let Some(expected) = &params.mode {
+            match parse_octal_mode(expected) {
+                Ok(bits) if bits == mode => {}
+                Ok(bits) => mismatches.push(format!("mode {:04o} (expected {:04o})", mode, bits)),
+                Err(e) => return CheckExecutionResult::error(e).with_evidence(evidence),
+            }
+        }
+
+        if let Some(max_mode) = &params.max_mode {
+            match parse_octal_mode(max_mode) {
+                Ok(bits) if mode & !bits == 0 => {}
+                Ok(bits) => mismatches.push(format!(
+                    "mode {:04o} exceeds {:04o} (extra bits {:04o})",
+                    mode,
+                    bits,
+                    mode & !bits
+                )),
+                Err(e) => return CheckExecutionResult::error(e).with_evidence(evidence),
+            }
+        }
+
+        if let Some(expected) = &params.owner {
+            match lookup_user(expected) {
+                Ok(Some(user)) if user.uid == metadata.uid() => {}
+                Ok(Some(_)) => mismatches.push(format!(
+                    "owner {} (expected {})",
+                    owner.as_deref().unwrap_or("unknown"),
+                    expected
+                )),
+                // A numeric uid without a passwd entry can still own files
+                Ok(None) if expected.parse::<u32>() == Ok(metadata.uid()) => {}
+                Ok(None) => mismatches.push(format!("expected owner {} does not exist", expected)),
+                Err(e) => return CheckExecutionResult::error(e).with_evidence(evidence),
+            }
+        }
+
+        if let Some(expected) = &params.group {
+            match lookup_group(expected) {
+                Ok(Some(gid)) if gid == metadata.gid() => {}
+                Ok(Some(_)) => mismatches.push(format!(
+                    "group {} (expected {})",
+                    group.as_deref().unwrap_or("unknown"),
+                    expected
+                )),
+                Ok(None) => mismatches.push(format!("expected group {} does not exist", expected)),
+                Err(e) => return CheckExecutionResult::error(e).with_evidence(evidence),
+            }
+        }
+
+        if let Some(expected) = params.file_type {
+            if kind != Some(expected) {
+                mismatches.push(format!(
+                    "type {} (expected {})",
+                    kind.map_or("other".to_string(), |k| k.to_string()),
+                    expected
+                ));
+            }
+        }
+
+        let result = if mismatches.is_empty() {
+            CheckExecutionResult::pass(Some(format!(
+                "Permissions match: {} {:04o} {}:{}",
+                params.path,
+                mode,
+                owner.unwrap_or_else(|| metadata.uid().to_string()),
+                group.unwrap_or_else(|| metadata.gid().to_string())
+            )))
+        } else {
+            CheckExecutionResult::fail(format!(
+                "Permissions mismatch on {}: {}",
+                params.path,
+                mismatches.join("; ")
+            ))
+        };
+        result.with_evidence(evidence)
+    }
+
+    #[cfg(not(unix))]
+    fn execute(
+        &self,
+        _params: &FilePermissionsParams,
+        _ctx: &CheckContext,
+    ) -> CheckExecutionResult {
+        CheckExecutionResult::skipped("File permission checks are only available on Unix")
+    }
+}
@@ -0,0 +1,154 @@
+use std::fs;
+
+use common::{CheckTypeId, KernelModuleParams};
+use serde_json::json;
+
+use crate::checks::dropin::conf_files;
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+const PROC_MODULES: &str = "/proc/modules";
+
+/// Directories modprobe reads, in order of precedence
+const MODPROBE_DIRS: &[&str] = &[
+    "/etc/modprobe.d",
+    "/run/modprobe.d",
+    "/usr/local/lib/modprobe.d",
+    "/usr/lib/modprobe.d",
+    "/lib/modprobe.d",
+];
+
+/// Commands that make `install` directives refuse to load a module
+const DISABLING_COMMANDS: &[&str] = &["false", "true"];
+
+pub struct KernelModule;
+
+impl Check for KernelModule {
+    type Params = KernelModuleParams;
+    const TYPE: CheckTypeId = CheckTypeId::KernelModule;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux]
+    }
+
+    fn execute(&self, params: &KernelModuleParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        // The kernel reports module names with underscores
+        let name = params.name.replace('-', "_");
+
+        let mut evidence = json!({ "name": name });
+        let mut problems = Vec::new();
+        let mut found = Vec::new();
+
+        if params.not_loaded {
+            let modules = match fs::read_to_string(PROC_MODULES) {
+                Ok(m) => m,
+                // Kernels without loadable module support have no /proc/modules
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => {
+                    return CheckExecutionResult::error(format!(
+                        "Failed to read {}: {}",
+                        PROC_MODULES, e
+                    ))
+                }
+            };
+            let loaded = modules
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .any(|module| module == name);
+            evidence["loaded"] = json!(loaded);
+            if loaded {
+                problems.push(format!("{} is loaded", name));
+            } else {
+                found.push(format!("{} is not loaded", name));
+            }
+        }
+
+        if params.disabled {
+            let config = modprobe_config(&name);
+            evidence["install"] = json!(config
+                .install
+                .iter()
+                .map(|(command, file)| json!({ "command": command, "file": file }))
+                .collect::<Vec<_>>());
+            evidence["blacklist"] = json!(config.blacklist);
+            evidence["files"] = json!(config.files);
+
+            // modprobe runs the first install command in file name order
+            let effective = config.install.first();
+            evidence["effective_install"] = json!(effective.map(|(command, _)| command));
+            let disabling = effective.filter(|(command, _)| {
+                command
+                    .split_whitespace()
+                    .next()
+                    .and_then(|program| program.rsplit('/').next())
+                    .is_some_and(|program| DISABLING_COMMANDS.contains(&program))
+            });
+            match (disabling, config.blacklist.first()) {
+                (Some((command, file)), _) => {
+                    found.push(format!("{} sets install {} {}", file, name, command))
+                }
+                (None, Some(file)) => found.push(format!("{} blacklists {}", file, name)),
+                (None, None) => problems.push(format!(
+                    "{} is not disabled by an install command or blacklist in modprobe configuration",
+                    name
+                )),
+            }
+        }
+
+        let result = if problems.is_empty() {
+            CheckExecutionResult::pass(Some(found.join("; ")))
+        } else {
+            CheckExecutionResult::fail(problems.join("; "))
+        };
+        result.with_evidence(evidence)
+    }
+}
+
+/// Directives of the modprobe configuration that concern one module
+struct ModprobeConfig {
+    /// `install` commands with the file setting each
+    install: Vec<(String, String)>,
+    /// Files with a `blacklist` entry for the module
+    blacklist: Vec<String>,
+    /// Files read, in order
+    files: Vec<String>,
+}
+
+/// Read the `install` and `blacklist` directives for `name` from all
+/// modprobe configuration files, in order of their names
+fn modprobe_config(name: &str) -> ModprobeConfig {
+    let mut config = ModprobeConfig {
+        install: Vec::new(),
+        blacklist: Vec::new(),
+        files: Vec::new(),
+    };
+    for path in conf_files(MODPROBE_DIRS) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let file = path.display().to_string();
+
+        // A trailing backslash continues a directive on the next line
+        let content = content.replace("\\\n", " ");
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let directive = words.next().unwrap_or("");
+            let module = words.next().unwrap_or("").replace('-', "_");
+            if module != name {
+                continue;
+            }
+            match directive {
+                "install" => config
+                    .install
+                    .push((words.collect::<Vec<_>>().join(" "), file.clone())),
+                "blacklist" => config.blacklist.push(file.clone()),
+                _ => {}
+            }
+        }
+        config.files.push(file);
+    }
+    config
+}
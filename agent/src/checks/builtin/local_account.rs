@@ -0,0 +1,368 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+use common::{AccountRule, CheckTypeId, LocalAccountParams};
+use serde_json::json;
+
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+const PASSWD: &str = "/etc/passwd";
+const SHADOW: &str = "/etc/shadow";
+const GROUP: &str = "/etc/group";
+
+pub struct LocalAccount;
+
+impl Check for LocalAccount {
+    type Params = LocalAccountParams;
+    const TYPE: CheckTypeId = CheckTypeId::LocalAccount;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux]
+    }
+
+    fn execute(&self, params: &LocalAccountParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let outcome = match params.rule {
+            AccountRule::UserExists | AccountRule::UserAbsent => user_presence(params),
+            AccountRule::UidZero => uid_zero(params),
+            AccountRule::EmptyPassword => empty_password(params),
+            AccountRule::GroupMembers => group_members(params),
+            AccountRule::PasswordAging => password_aging(params),
+        };
+        match outcome {
+            Ok(result) => result,
+            Err(e) => CheckExecutionResult::error(e),
+        }
+    }
+}
+
+/// An /etc/passwd entry
+struct PasswdEntry {
+    name: String,
+    password: String,
+    uid: u32,
+    gid: u32,
+}
+
+/// An /etc/shadow entry; unset aging fields are `None`
+struct ShadowEntry {
+    name: String,
+    hash: String,
+    min_days: Option<u32>,
+    max_days: Option<u32>,
+    warn_days: Option<u32>,
+    inactive_days: Option<u32>,
+}
+
+/// An /etc/group entry
+struct GroupEntry {
+    name: String,
+    gid: u32,
+    members: Vec<String>,
+}
+
+/// Colon-separated records of an account database, skipping comments and
+/// NIS `+`/`-` entries
+fn read_records(path: &str) -> Result<Vec<Vec<String>>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter(|line| !line.starts_with(['#', '+', '-']))
+        .map(|line| line.split(':').map(str::to_string).collect())
+        .collect())
+}
+
+fn field(record: &[String], index: usize) -> &str {
+    record.get(index).map(String::as_str).unwrap_or("")
+}
+
+fn day_field(record: &[String], index: usize) -> Option<u32> {
+    field(record, index).trim().parse().ok()
+}
+
+fn read_passwd() -> Result<Vec<PasswdEntry>, String> {
+    Ok(read_records(PASSWD)?
+        .into_iter()
+        .filter_map(|record| {
+            Some(PasswdEntry {
+                uid: field(&record, 2).parse().ok()?,
+                gid: field(&record, 3).parse().ok()?,
+                password: field(&record, 1).to_string(),
+                name: field(&record, 0).to_string(),
+            })
+        })
+        .collect())
+}
+
+fn read_shadow() -> Result<Vec<ShadowEntry>, String> {
+    Ok(read_records(SHADOW)?
+        .into_iter()
+        .map(|record| ShadowEntry {
+            name: field(&record, 0).to_string(),
+            hash: field(&record, 1).to_string(),
+            min_days: day_field(&record, 3),
+            max_days: day_field(&record, 4),
+            warn_days: day_field(&record, 5),
+            inactive_days: day_field(&record, 6),
+        })
+        .collect())
+}
+
+fn read_groups() -> Result<Vec<GroupEntry>, String> {
+    Ok(read_records(GROUP)?
+        .into_iter()
+        .filter_map(|record| {
+            Some(GroupEntry {
+                gid: field(&record, 2).parse().ok()?,
+                name: field(&record, 0).to_string(),
+                members: field(&record, 3)
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|m| !m.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// Pass when no account is offending, else fail listing them
+fn offending_result(
+    offending: Vec<String>,
+    pass_message: String,
+    fail_prefix: &str,
+    evidence: serde_json::Value,
+) -> CheckExecutionResult {
+    let mut evidence = evidence;
+    evidence["offending"] = json!(offending);
+    let result = if offending.is_empty() {
+        CheckExecutionResult::pass(Some(pass_message))
+    } else {
+        CheckExecutionResult::fail(format!("{}: {}", fail_prefix, offending.join(", ")))
+    };
+    result.with_evidence(evidence)
+}
+
+fn user_presence(params: &LocalAccountParams) -> Result<CheckExecutionResult, String> {
+    let user = params.user.as_deref().unwrap_or_default();
+    let passwd = read_passwd()?;
+    let entry = passwd.iter().find(|e| e.name == user);
+
+    let evidence = json!({
+        "rule": params.rule,
+        "user": user,
+        "exists": entry.is_some(),
+        "uid": entry.map(|e| e.uid),
+    });
+    let result = match (params.rule, entry) {
+        (AccountRule::UserExists, Some(e)) => {
+            CheckExecutionResult::pass(Some(format!("User {} exists (uid {})", user, e.uid)))
+        }
+        (AccountRule::UserExists, None) => {
+            CheckExecutionResult::fail(format!("User {} does not exist", user))
+        }
+        (_, Some(e)) => CheckExecutionResult::fail(format!("User {} exists (uid {})", user, e.uid)),
+        (_, None) => CheckExecutionResult::pass(Some(format!("User {} does not exist", user))),
+    };
+    Ok(result.with_evidence(evidence))
+}
+
+fn uid_zero(params: &LocalAccountParams) -> Result<CheckExecutionResult, String> {
+    let offending = read_passwd()?
+        .into_iter()
+        .filter(|e| e.uid == 0 && e.name != "root" && !params.allowed.contains(&e.name))
+        .map(|e| e.name)
+        .collect();
+    Ok(offending_result(
+        offending,
+        "Only root has UID 0".to_string(),
+        "Accounts other than root with UID 0",
+        json!({ "rule": params.rule, "allowed": params.allowed }),
+    ))
+}
+
+fn empty_password(params: &LocalAccountParams) -> Result<CheckExecutionResult, String> {
+    let passwd = read_passwd()?;
+    let shadow = read_shadow()?;
+
+    // The password is in /etc/passwd unless that holds "x"
+    let mut offending: BTreeSet<String> = passwd
+        .iter()
+        .filter(|e| e.password.is_empty())
+        .map(|e| e.name.clone())
+        .collect();
+    offending.extend(
+        shadow
+            .iter()
+            .filter(|s| s.hash.is_empty())
+            .filter(|s| passwd.iter().any(|e| e.name == s.name && e.password == "x"))
+            .map(|s| s.name.clone()),
+    );
+    offending.retain(|name| !params.allowed.contains(name));
+
+    Ok(offending_result(
+        offending.into_iter().collect(),
+        "No account has an empty password".to_string(),
+        "Accounts with an empty password",
+        json!({ "rule": params.rule, "allowed": params.allowed }),
+    ))
+}
+
+fn group_members(params: &LocalAccountParams) -> Result<CheckExecutionResult, String> {
+    let group_name = params.group.as_deref().unwrap_or_default();
+    let groups = read_groups()?;
+    let passwd = read_passwd()?;
+
+    // Members are those listed in /etc/group and those with it as primary group
+    let group = groups.iter().find(|g| g.name == group_name);
+    let members: BTreeSet<String> = match group {
+        Some(group) => group
+            .members
+            .iter()
+            .cloned()
+            .chain(
+                passwd
+                    .iter()
+                    .filter(|e| e.gid == group.gid)
+                    .map(|e| e.name.clone()),
+            )
+            .collect(),
+        None => BTreeSet::new(),
+    };
+
+    let evidence = json!({
+        "rule": params.rule,
+        "group": group_name,
+        "exists": group.is_some(),
+        "members": members,
+        "allowed": params.allowed,
+    });
+
+    if let Some(user) = &params.user {
+        let result = if members.contains(user) {
+            CheckExecutionResult::pass(Some(format!("{} is a member of {}", user, group_name)))
+        } else if group.is_none() {
+            CheckExecutionResult::fail(format!("Group {} does not exist", group_name))
+        } else {
+            CheckExecutionResult::fail(format!("{} is not a member of {}", user, group_name))
+        };
+        return Ok(result.with_evidence(evidence));
+    }
+
+    let offending = members
+        .iter()
+        .filter(|m| !params.allowed.contains(m))
+        .cloned()
+        .collect();
+    Ok(offending_result(
+        offending,
+        match group {
+            Some(_) => format!("{} has only allowed members", group_name),
+            None => format!("Group {} does not exist", group_name),
+        },
+        &format!("Members of {} not allowed", group_name),
+        evidence,
+    ))
+}
+
+fn password_aging(params: &LocalAccountParams) -> Result<CheckExecutionResult, String> {
+    let shadow = read_shadow()?;
+
+    // Accounts without a usable password cannot log in with one
+    let usable = |s: &ShadowEntry| !s.hash.is_empty() && !s.hash.starts_with(['!', '*']);
+    let accounts: Vec<&ShadowEntry> = shadow
+        .iter()
+        .filter(|s| params.user.as_ref().is_none_or(|u| *u == s.name))
+        .collect();
+    let locked: Vec<&str> = accounts
+        .iter()
+        .filter(|s| !usable(s))
+        .map(|s| s.name.as_str())
+        .collect();
+
+    if let Some(user) = &params.user {
+        let evidence = json!({
+            "rule": params.rule,
+            "user": user,
+            "exists": !accounts.is_empty(),
+            "locked": !locked.is_empty(),
+        });
+        if accounts.is_empty() {
+            return Ok(CheckExecutionResult::fail(format!(
+                "User {} does not exist in {}",
+                user, SHADOW
+            ))
+            .with_evidence(evidence));
+        }
+        if !locked.is_empty() {
+            return Ok(CheckExecutionResult::pass(Some(format!(
+                "User {} is locked or has no password, so password aging does not apply",
+                user
+            )))
+            .with_evidence(evidence));
+        }
+    }
+
+    let mut offending = Vec::new();
+    let mut problems = Vec::new();
+    for entry in accounts
+        .iter()
+        .filter(|s| usable(s) && !params.allowed.contains(&s.name))
+    {
+        let mut account_problems = Vec::new();
+        let show = |days: Option<u32>| days.map_or_else(|| "unset".to_string(), |d| d.to_string());
+
+        if let Some(limit) = params.max_days {
+            if entry.max_days.is_none_or(|d| d > limit) {
+                account_problems.push(format!("max_days {}", show(entry.max_days)));
+            }
+        }
+        if let Some(limit) = params.min_days {
+            if entry.min_days.unwrap_or(0) < limit {
+                account_problems.push(format!("min_days {}", show(entry.min_days)));
+            }
+        }
+        if let Some(limit) = params.warn_days {
+            if entry.warn_days.unwrap_or(0) < limit {
+                account_problems.push(format!("warn_days {}", show(entry.warn_days)));
+            }
+        }
+        if let Some(limit) = params.inactive_days {
+            if entry.inactive_days.is_none_or(|d| d > limit) {
+                account_problems.push(format!("inactive_days {}", show(entry.inactive_days)));
+            }
+        }
+
+        if !account_problems.is_empty() {
+            offending.push(format!("{} ({})", entry.name, account_problems.join(", ")));
+            problems.push(json!({
+                "account": entry.name,
+                "min_days": entry.min_days,
+                "max_days": entry.max_days,
+                "warn_days": entry.warn_days,
+                "inactive_days": entry.inactive_days,
+            }));
+        }
+    }
+
+    let evidence = json!({
+        "rule": params.rule,
+        "user": params.user,
+        "expected": {
+            "max_days": params.max_days,
+            "min_days": params.min_days,
+            "warn_days": params.warn_days,
+            "inactive_days": params.inactive_days,
+        },
+        "accounts": problems,
+        "locked_accounts": locked,
+        "allowed": params.allowed,
+    });
+    Ok(offending_result(
+        offending,
+        "Password aging is within limits".to_string(),
+        "Password aging out of limits",
+        evidence,
+    ))
+}
@@ -0,0 +1,72 @@
+//! Check types that ship with the agent
+
+#[cfg(feature = "command-checks")]
+mod command_output;
+mod config_setting;
+mod file_content;
+mod file_exists;
+mod file_hash;
+mod file_permissions;
+mod kernel_module;
+mod local_account;
+mod mount_options;
+mod package_installed;
+mod port_open;
+mod process_running;
+mod registry_key;
+mod resource_threshold;
+mod service_state;
+mod sshd_setting;
+mod structured_config;
+mod sysctl;
+
+#[cfg(feature = "command-checks")]
+use super::allowlist::CommandAllowlist;
+use super::CheckRegistry;
+use crate::config::Config;
+
+/// Register every built-in check enabled in this build
+pub fn register_all(registry: &mut CheckRegistry, config: &Config) -> anyhow::Result<()> {
+    registry.register(file_exists::FileExists);
+    registry.register(file_content::FileContent);
+    registry.register(file_permissions::FilePermissions);
+    registry.register(file_hash::FileHash);
+    registry.register(registry_key::RegistryKey);
+    registry.register(config_setting::ConfigSetting);
+    registry.register(structured_config::StructuredConfig);
+    registry.register(sshd_setting::SshdSetting);
+    registry.register(service_state::ServiceState);
+    registry.register(package_installed::PackageInstalled);
+    registry.register(sysctl::Sysctl);
+    registry.register(kernel_module::KernelModule);
+    registry.register(local_account::LocalAccount);
+    registry.register(mount_options::MountOptions);
+    registry.register(resource_threshold::ResourceThreshold);
+    registry.register(process_running::ProcessRunning);
+    registry.register(port_open::PortOpen);
+    #[cfg(feature = "command-checks")]
+    {
+        let allowlist = match &config.command_allowlist_file {
+            Some(path) => Some(CommandAllowlist::load(path)?),
+            None => None,
+        };
+        // Without a way to trust a command, none may run
+        if allowlist.is_none() && config.check_signing_public_key.is_none() {
+            tracing::warn!(
+                "command_output checks are disabled; \
+                 set CHECK_SIGNING_PUBLIC_KEY or COMMAND_ALLOWLIST_FILE to enable them"
+            );
+        } else {
+            registry.register(command_output::CommandOutput::new(
+                config.allow_root_commands,
+                allowlist,
+            ));
+        }
+    }
+    #[cfg(not(feature = "command-checks"))]
+    if config.command_allowlist_file.is_some() {
+        tracing::warn!("COMMAND_ALLOWLIST_FILE is ignored: built without command checks");
+    }
+
+    Ok(())
+}
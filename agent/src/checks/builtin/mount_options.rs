@@ -0,0 +1,154 @@
+use std::fs;
+
+use common::{CheckTypeId, MountOptionsParams};
+use serde_json::json;
+
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+const MOUNTINFO: &str = "/proc/self/mountinfo";
+
+pub struct MountOptions;
+
+impl Check for MountOptions {
+    type Params = MountOptionsParams;
+    const TYPE: CheckTypeId = CheckTypeId::MountOptions;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux]
+    }
+
+    fn execute(&self, params: &MountOptionsParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let mountinfo = match fs::read_to_string(MOUNTINFO) {
+            Ok(m) => m,
+            Err(e) => {
+                return CheckExecutionResult::error(format!("Failed to read {}: {}", MOUNTINFO, e))
+            }
+        };
+
+        let path = match params.path.trim_end_matches('/') {
+            "" => "/",
+            path => path,
+        };
+
+        // Of mounts stacked on the same point, the last one is visible
+        let Some(mount) = mountinfo
+            .lines()
+            .rev()
+            .filter_map(parse_mountinfo_line)
+            .find(|m| m.mount_point == path)
+        else {
+            return CheckExecutionResult::fail(format!("{} is not a separate mount", path))
+                .with_evidence(json!({ "path": path, "mounted": false }));
+        };
+
+        let missing: Vec<&str> = params
+            .required
+            .iter()
+            .filter(|option| !mount.options.contains(option))
+            .map(String::as_str)
+            .collect();
+        let present: Vec<&str> = params
+            .forbidden
+            .iter()
+            .filter(|option| mount.options.contains(option))
+            .map(String::as_str)
+            .collect();
+
+        let evidence = json!({
+            "path": path,
+            "mounted": true,
+            "fs_type": mount.fs_type,
+            "source": mount.source,
+            "options": mount.options,
+            "missing": missing,
+            "forbidden_present": present,
+        });
+
+        let mut problems = Vec::new();
+        if !missing.is_empty() {
+            problems.push(format!("missing {}", missing.join(",")));
+        }
+        if !present.is_empty() {
+            problems.push(format!("has {}", present.join(",")));
+        }
+
+        let result = if problems.is_empty() {
+            CheckExecutionResult::pass(Some(format!(
+                "{} is mounted ({}) with {}",
+                path,
+                mount.fs_type,
+                mount.options.join(",")
+            )))
+        } else {
+            CheckExecutionResult::fail(format!(
+                "{} is mounted with {}: {}",
+                path,
+                mount.options.join(","),
+                problems.join("; ")
+            ))
+        };
+        result.with_evidence(evidence)
+    }
+}
+
+/// A mount from /proc/self/mountinfo
+struct Mount {
+    mount_point: String,
+    fs_type: String,
+    source: String,
+    /// Per-mount options followed by the superblock options
+    options: Vec<String>,
+}
+
+/// Parse a mountinfo line:
+/// `id parent major:minor root mount_point options [optional...] - fs_type source super_options`
+fn parse_mountinfo_line(line: &str) -> Option<Mount> {
+    let (mount_fields, fs_fields) = line.split_once(" - ")?;
+    let mut mount_fields = mount_fields.split(' ');
+    let mount_point = unescape(mount_fields.nth(4)?);
+    let mount_options = mount_fields.next()?;
+
+    let mut fs_fields = fs_fields.split(' ');
+    let fs_type = fs_fields.next()?.to_string();
+    let source = unescape(fs_fields.next()?);
+    let super_options = fs_fields.next().unwrap_or("");
+
+    let mut options: Vec<String> = Vec::new();
+    for option in mount_options.split(',').chain(super_options.split(',')) {
+        if !option.is_empty() && !options.iter().any(|o| o == option) {
+            options.push(option.to_string());
+        }
+    }
+
+    Some(Mount {
+        mount_point,
+        fs_type,
+        source,
+        options,
+    })
+}
+
+/// The kernel writes space, tab, newline and backslash in paths as octal escapes
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes
+            .get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match escape {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
@@ -0,0 +1,97 @@
+use common::{CheckTypeId, PackageInstalledParams};
+use serde_json::json;
+
+use crate::checks::compare::ordering_holds;
+use crate::checks::packages::{compare_package_versions, detect_manager, installed_versions};
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+pub struct PackageInstalled;
+
+impl Check for PackageInstalled {
+    type Params = PackageInstalledParams;
+    const TYPE: CheckTypeId = CheckTypeId::PackageInstalled;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux]
+    }
+
+    fn execute(
+        &self,
+        params: &PackageInstalledParams,
+        _ctx: &CheckContext,
+    ) -> CheckExecutionResult {
+        let Some(manager) = params.manager.or_else(detect_manager) else {
+            return CheckExecutionResult::skipped("No dpkg or RPM database on this host");
+        };
+
+        let versions = match installed_versions(manager, &params.name) {
+            Ok(v) => v,
+            Err(e) => return CheckExecutionResult::error(e),
+        };
+        let evidence = json!({
+            "name": params.name,
+            "manager": manager,
+            "installed": !versions.is_empty(),
+            "versions": versions,
+            "expected": {
+                "installed": params.installed,
+                "operator": params.operator,
+                "version": params.version,
+            },
+        });
+
+        let result = if versions.is_empty() {
+            if params.installed {
+                CheckExecutionResult::fail(format!("{} is not installed", params.name))
+            } else {
+                CheckExecutionResult::pass(Some(format!("{} is not installed", params.name)))
+            }
+        } else if !params.installed {
+            CheckExecutionResult::fail(format!(
+                "{} {} is installed",
+                params.name,
+                versions.join(", ")
+            ))
+        } else if let Some(expected) = &params.version {
+            // Every installed instance must match, so an old copy left
+            // alongside a new one still fails
+            let mismatched: Vec<&String> = versions
+                .iter()
+                .filter(|v| {
+                    !ordering_holds(
+                        params.operator,
+                        compare_package_versions(manager, v, expected),
+                    )
+                })
+                .collect();
+            if mismatched.is_empty() {
+                CheckExecutionResult::pass(Some(format!(
+                    "{} {} is installed ({} {})",
+                    params.name,
+                    versions.join(", "),
+                    params.operator,
+                    expected
+                )))
+            } else {
+                CheckExecutionResult::fail(format!(
+                    "{} {} is installed (expected {} {})",
+                    params.name,
+                    mismatched
+                        .iter()
+                        .map(|v| v.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    params.operator,
+                    expected
+                ))
+            }
+        } else {
+            CheckExecutionResult::pass(Some(format!(
+                "{} {} is installed",
+                params.name,
+                versions.join(", ")
+            )))
+        };
+        result.with_evidence(evidence)
+    }
+}
@@ -0,0 +1,26 @@
+use std::net::TcpListener;
+
+use common::{CheckTypeId, PortOpenParams};
+use serde_json::json;
+
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+pub struct PortOpen;
+
+impl Check for PortOpen {
+    type Params = PortOpenParams;
+    const TYPE: CheckTypeId = CheckTypeId::PortOpen;
+
+    fn execute(&self, params: &PortOpenParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        // Try to bind to the port - if it fails, something is listening
+        let listening = TcpListener::bind(("127.0.0.1", params.port)).is_err();
+        let evidence = json!({ "port": params.port, "listening": listening });
+
+        let result = if listening {
+            CheckExecutionResult::pass(Some(format!("Port {} is open/listening", params.port)))
+        } else {
+            CheckExecutionResult::fail(format!("Port {} is not open/listening", params.port))
+        };
+        result.with_evidence(evidence)
+    }
+}
@@ -0,0 +1,44 @@
+use common::{CheckTypeId, ProcessRunningParams};
+use serde_json::json;
+
+use crate::checks::evidence::EVIDENCE_MAX_LINES;
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+pub struct ProcessRunning;
+
+impl Check for ProcessRunning {
+    type Params = ProcessRunningParams;
+    const TYPE: CheckTypeId = CheckTypeId::ProcessRunning;
+
+    fn execute(&self, params: &ProcessRunningParams, ctx: &CheckContext) -> CheckExecutionResult {
+        let name_lower = params.name.to_lowercase();
+
+        let mut matching: Vec<(u32, String)> = ctx
+            .system
+            .processes()
+            .values()
+            .map(|p| (p.pid().as_u32(), p.name().to_string_lossy().into_owned()))
+            .filter(|(_, name)| name.to_lowercase().contains(&name_lower))
+            .collect();
+        matching.sort();
+
+        let evidence = json!({
+            "name": params.name,
+            "processes": matching
+                .iter()
+                .take(EVIDENCE_MAX_LINES)
+                .map(|(pid, name)| json!({ "pid": pid, "name": name }))
+                .collect::<Vec<_>>(),
+        });
+
+        let result = match matching.first() {
+            Some((_, name)) => {
+                CheckExecutionResult::pass(Some(format!("Process is running: {}", name)))
+            }
+            None => CheckExecutionResult::fail(format!("Process not running: {}", params.name)),
+        };
+        result
+            .with_evidence(evidence)
+            .with_value(matching.len() as f64)
+    }
+}
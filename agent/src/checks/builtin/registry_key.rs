@@ -0,0 +1,166 @@
+use common::{CheckTypeId, RegistryKeyParams};
+
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+pub struct RegistryKey;
+
+impl Check for RegistryKey {
+    type Params = RegistryKeyParams;
+    const TYPE: CheckTypeId = CheckTypeId::RegistryKey;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Windows]
+    }
+
+    #[cfg(target_os = "windows")]
+    fn execute(&self, params: &RegistryKeyParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        use common::ComparisonOperator;
+        use serde_json::json;
+        use winreg::enums::*;
+        use winreg::RegKey;
+
+        use crate::checks::compare::{compare, expectation, value_text};
+
+        // Parse the registry path
+        let (hkey, subkey) = if params.path.starts_with("HKEY_LOCAL_MACHINE\\")
+            || params.path.starts_with("HKLM\\")
+        {
+            let path = params
+                .path
+                .trim_start_matches("HKEY_LOCAL_MACHINE\\")
+                .trim_start_matches("HKLM\\");
+            (HKEY_LOCAL_MACHINE, path)
+        } else if params.path.starts_with("HKEY_CURRENT_USER\\")
+            || params.path.starts_with("HKCU\\")
+        {
+            let path = params
+                .path
+                .trim_start_matches("HKEY_CURRENT_USER\\")
+                .trim_start_matches("HKCU\\");
+            (HKEY_CURRENT_USER, path)
+        } else {
+            return CheckExecutionResult::error(format!(
+                "Unsupported registry hive in path: {}",
+                params.path
+            ));
+        };
+
+        let hkey = RegKey::predef(hkey);
+        let key = match hkey.open_subkey(subkey) {
+            Ok(k) => k,
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return CheckExecutionResult::error(format!(
+                    "Failed to open registry key {}: {}",
+                    params.path, e
+                ))
+            }
+            Err(e) => {
+                // A missing key also means its value is missing
+                let missing_passes = params.value_name.is_some()
+                    && compare(params.operator, None, None, params.if_missing) == Ok(true);
+                let result = if missing_passes {
+                    CheckExecutionResult::pass(Some(format!(
+                        "Registry key not set: {}",
+                        params.path
+                    )))
+                } else {
+                    CheckExecutionResult::fail(format!(
+                        "Registry key not found: {} ({})",
+                        params.path, e
+                    ))
+                };
+                return result.with_evidence(json!({ "path": params.path, "exists": false }));
+            }
+        };
+
+        let Some(value_name) = &params.value_name else {
+            return CheckExecutionResult::pass(Some(format!(
+                "Registry key exists: {}",
+                params.path
+            )))
+            .with_evidence(json!({ "path": params.path, "exists": true }));
+        };
+
+        let value = key
+            .get_raw_value(value_name)
+            .and_then(|raw| registry_value(&raw));
+        // Only a missing value counts as not set
+        if let Err(e) = &value {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return CheckExecutionResult::error(format!(
+                    "Failed to read registry value {}: {}",
+                    value_name, e
+                ))
+                .with_evidence(json!({ "path": params.path, "exists": true }));
+            }
+        }
+        let observed = value.as_ref().ok();
+        let shown = observed.and_then(value_text).unwrap_or_default();
+        let evidence = json!({
+            "path": params.path,
+            "exists": true,
+            "value_name": value_name,
+            "observed": observed,
+            "operator": params.operator,
+            "expected": params.expected,
+        });
+
+        // Without an operand only the value's existence is checked
+        if params.expected.is_none() && params.operator == ComparisonOperator::Equals {
+            let result = match &value {
+                Ok(_) => CheckExecutionResult::pass(Some(format!(
+                    "Registry value exists: {} = {}",
+                    value_name, shown
+                ))),
+                Err(e) => CheckExecutionResult::fail(format!(
+                    "Registry value not found: {} ({})",
+                    value_name, e
+                )),
+            };
+            return result.with_evidence(evidence);
+        }
+
+        let expected = expectation(params.operator, params.expected.as_ref());
+        let result = match compare(
+            params.operator,
+            observed,
+            params.expected.as_ref(),
+            params.if_missing,
+        ) {
+            Ok(true) => CheckExecutionResult::pass(Some(match &value {
+                Ok(_) => format!(
+                    "Registry value matches: {} = {} ({})",
+                    value_name, shown, expected
+                ),
+                Err(_) => format!("Registry value not set: {}", value_name),
+            })),
+            Ok(false) => CheckExecutionResult::fail(match &value {
+                Ok(_) => format!(
+                    "Registry value mismatch: {} = {} (expected {})",
+                    value_name, shown, expected
+                ),
+                Err(e) => format!("Registry value not found: {} ({})", value_name, e),
+            }),
+            Err(e) => CheckExecutionResult::error(format!("Registry value {}: {}", value_name, e)),
+        };
+        result.with_evidence(evidence)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn execute(&self, _params: &RegistryKeyParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        CheckExecutionResult::skipped("Registry checks are only available on Windows")
+    }
+}
+
+/// Registry value as JSON: DWORD and QWORD values are numbers, others text
+#[cfg(target_os = "windows")]
+fn registry_value(raw: &winreg::RegValue) -> std::io::Result<serde_json::Value> {
+    use winreg::enums::*;
+    use winreg::types::FromRegValue;
+
+    Ok(match raw.vtype {
+        REG_DWORD => u32::from_reg_value(raw)?.into(),
+        REG_QWORD => u64::from_reg_value(raw)?.into(),
+        _ => String::from_reg_value(raw)?.into(),
+    })
+}
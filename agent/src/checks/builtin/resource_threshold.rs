@@ -0,0 +1,232 @@
+use std::path::Path;
+
+use common::{CheckTypeId, ResourceKind, ResourceThresholdParams};
+use serde_json::json;
+use sysinfo::{Disks, System};
+
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+pub struct ResourceThreshold;
+
+impl Check for ResourceThreshold {
+    type Params = ResourceThresholdParams;
+    const TYPE: CheckTypeId = CheckTypeId::ResourceThreshold;
+
+    fn execute(
+        &self,
+        params: &ResourceThresholdParams,
+        ctx: &CheckContext,
+    ) -> CheckExecutionResult {
+        match params.resource {
+            ResourceKind::Disk | ResourceKind::Inodes => filesystems(params),
+            ResourceKind::Memory => {
+                let total = ctx.system.total_memory();
+                let used = total.saturating_sub(ctx.system.available_memory());
+                single(params, "Memory", percent(used, total), used, total)
+            }
+            ResourceKind::Swap => {
+                let total = ctx.system.total_swap();
+                let used = ctx.system.used_swap();
+                single(params, "Swap", percent(used, total), used, total)
+            }
+            ResourceKind::Load => load(params, ctx),
+        }
+    }
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 * 100.0 / total as f64
+    }
+}
+
+/// Memory or swap usage against the threshold
+fn single(
+    params: &ResourceThresholdParams,
+    label: &str,
+    used_percent: f64,
+    used: u64,
+    total: u64,
+) -> CheckExecutionResult {
+    let evidence = json!({
+        "resource": params.resource,
+        "max": params.max,
+        "used_percent": used_percent,
+        "used_bytes": used,
+        "total_bytes": total,
+    });
+    let shown = format!(
+        "{} usage is {:.1}% (max {}%)",
+        label, used_percent, params.max
+    );
+    let result = if used_percent <= params.max {
+        CheckExecutionResult::pass(Some(shown))
+    } else {
+        CheckExecutionResult::fail(shown)
+    };
+    result.with_evidence(evidence).with_value(used_percent)
+}
+
+/// Space or inode usage of one mount, or of every mounted filesystem
+fn filesystems(params: &ResourceThresholdParams) -> CheckExecutionResult {
+    let label = match params.resource {
+        ResourceKind::Inodes => "Inode",
+        _ => "Disk",
+    };
+
+    let wanted = params
+        .mount
+        .as_deref()
+        .map(|mount| match mount.trim_end_matches('/') {
+            "" => "/",
+            mount => mount,
+        });
+
+    let disks = Disks::new_with_refreshed_list();
+    let mut mounts: Vec<(String, String, f64)> = Vec::new();
+    for disk in disks.list() {
+        let mount = disk.mount_point().to_string_lossy().into_owned();
+        if mounts.iter().any(|(m, _, _)| *m == mount) || wanted.is_some_and(|w| w != mount) {
+            continue;
+        }
+        let usage = match params.resource {
+            ResourceKind::Inodes => match inode_usage(disk.mount_point()) {
+                Ok(Some(usage)) => usage,
+                // Filesystems such as btrfs allocate inodes dynamically
+                Ok(None) => continue,
+                Err(e) => return CheckExecutionResult::error(e),
+            },
+            _ if disk.total_space() == 0 => continue,
+            _ => percent(
+                disk.total_space().saturating_sub(disk.available_space()),
+                disk.total_space(),
+            ),
+        };
+        let fs_type = disk.file_system().to_string_lossy().into_owned();
+        mounts.push((mount, fs_type, usage));
+    }
+
+    if mounts.is_empty() {
+        return match &params.mount {
+            Some(mount) => CheckExecutionResult::error(format!(
+                "{} is not a mounted filesystem with {} usage",
+                mount,
+                label.to_lowercase()
+            )),
+            None => CheckExecutionResult::skipped("No mounted filesystems to check"),
+        };
+    }
+
+    let evidence = json!({
+        "resource": params.resource,
+        "max": params.max,
+        "mounts": mounts
+            .iter()
+            .map(|(mount, fs_type, usage)| {
+                json!({ "mount": mount, "file_system": fs_type, "used_percent": usage })
+            })
+            .collect::<Vec<_>>(),
+    });
+
+    let highest = mounts
+        .iter()
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(mount, _, usage)| (mount.clone(), *usage))
+        .unwrap_or_default();
+    let over: Vec<String> = mounts
+        .iter()
+        .filter(|(_, _, usage)| *usage > params.max)
+        .map(|(mount, _, usage)| format!("{} {:.1}%", mount, usage))
+        .collect();
+
+    let result = if over.is_empty() {
+        CheckExecutionResult::pass(Some(format!(
+            "{} usage is at most {:.1}% on {} (max {}%)",
+            label, highest.1, highest.0, params.max
+        )))
+    } else {
+        CheckExecutionResult::fail(format!(
+            "{} usage above {}%: {}",
+            label,
+            params.max,
+            over.join(", ")
+        ))
+    };
+    result.with_evidence(evidence).with_value(highest.1)
+}
+
+/// Percentage of inodes in use, or `None` when the filesystem has no fixed inode count
+#[cfg(unix)]
+fn inode_usage(mount: &Path) -> Result<Option<f64>, String> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(mount.as_os_str().as_bytes())
+        .map_err(|_| format!("Invalid mount point {}", mount.display()))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: path is NUL-terminated and stat is a valid statvfs to fill in
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(format!(
+            "statvfs {}: {}",
+            mount.display(),
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    let total = stat.f_files as u64;
+    let free = stat.f_ffree as u64;
+    if total == 0 {
+        return Ok(None);
+    }
+    Ok(Some(percent(total.saturating_sub(free), total)))
+}
+
+#[cfg(not(unix))]
+fn inode_usage(_mount: &Path) -> Result<Option<f64>, String> {
+    Err("Inode usage is only available on Unix".to_string())
+}
+
+fn load(params: &ResourceThresholdParams, ctx: &CheckContext) -> CheckExecutionResult {
+    if !cfg!(unix) {
+        return CheckExecutionResult::skipped("Load average is only available on Unix");
+    }
+
+    let average = System::load_average();
+    let load = match params.load_minutes {
+        1 => average.one,
+        15 => average.fifteen,
+        _ => average.five,
+    };
+    let cpus = ctx.system.cpus().len().max(1);
+    let observed = if params.per_cpu {
+        load / cpus as f64
+    } else {
+        load
+    };
+
+    let evidence = json!({
+        "resource": params.resource,
+        "max": params.max,
+        "load_minutes": params.load_minutes,
+        "per_cpu": params.per_cpu,
+        "load": { "one": average.one, "five": average.five, "fifteen": average.fifteen },
+        "cpus": cpus,
+        "observed": observed,
+    });
+
+    let shown = format!(
+        "{}-minute load average{} is {:.2} (max {})",
+        params.load_minutes,
+        if params.per_cpu { " per CPU" } else { "" },
+        observed,
+        params.max
+    );
+    let result = if observed <= params.max {
+        CheckExecutionResult::pass(Some(shown))
+    } else {
+        CheckExecutionResult::fail(shown)
+    };
+    result.with_evidence(evidence).with_value(observed)
+}
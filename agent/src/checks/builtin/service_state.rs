@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use common::{CheckTypeId, ServiceStateParams};
+use serde_json::json;
+
+use crate::checks::runner::run_tool;
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+/// Exists only while systemd is the init system, as `sd_booted()` checks
+const SYSTEMD_RUNTIME_DIR: &str = "/run/systemd/system";
+
+const SYSTEMCTL_TIMEOUT_SECS: u64 = 10;
+
+pub struct ServiceState;
+
+impl Check for ServiceState {
+    type Params = ServiceStateParams;
+    const TYPE: CheckTypeId = CheckTypeId::ServiceState;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux]
+    }
+
+    fn execute(&self, params: &ServiceStateParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        if !Path::new(SYSTEMD_RUNTIME_DIR).is_dir() {
+            return CheckExecutionResult::skipped("systemd is not running on this host");
+        }
+
+        let properties = match show_unit(&params.unit) {
+            Ok(p) => p,
+            Err(e) => return CheckExecutionResult::error(e),
+        };
+        let property = |name: &str| properties.get(name).map(String::as_str).unwrap_or("");
+
+        let unit = match property("Id") {
+            "" => params.unit.as_str(),
+            id => id,
+        };
+        let evidence = json!({
+            "unit": unit,
+            "load_state": property("LoadState"),
+            "active_state": property("ActiveState"),
+            "sub_state": property("SubState"),
+            "unit_file_state": property("UnitFileState"),
+            "expected": {
+                "active_state": params.active_state,
+                "sub_state": params.sub_state,
+                "unit_file_state": params.unit_file_state,
+            },
+        });
+
+        // A masked unit loads as "masked", so only a missing unit file means not installed
+        if property("LoadState") == "not-found" {
+            let result = if params.allow_missing {
+                CheckExecutionResult::pass(Some(format!("{} is not installed", unit)))
+            } else {
+                CheckExecutionResult::fail(format!("{} is not installed", unit))
+            };
+            return result.with_evidence(evidence);
+        }
+
+        let mut mismatches = Vec::new();
+        for (name, accepted) in [
+            ("ActiveState", &params.active_state),
+            ("SubState", &params.sub_state),
+            ("UnitFileState", &params.unit_file_state),
+        ] {
+            let observed = property(name);
+            if !accepted.is_empty() && !accepted.iter().any(|a| a == observed) {
+                mismatches.push(format!(
+                    "{} is {:?} (expected {})",
+                    name,
+                    observed,
+                    accepted.join(" or ")
+                ));
+            }
+        }
+
+        let result = if mismatches.is_empty() {
+            CheckExecutionResult::pass(Some(format!(
+                "{} is {} ({}), {}",
+                unit,
+                property("ActiveState"),
+                property("SubState"),
+                property("UnitFileState")
+            )))
+        } else {
+            CheckExecutionResult::fail(format!("{}: {}", unit, mismatches.join("; ")))
+        };
+        result.with_evidence(evidence)
+    }
+}
+
+/// Properties of a unit from `systemctl show`
+fn show_unit(unit: &str) -> Result<BTreeMap<String, String>, String> {
+    let run = run_tool(
+        "systemctl",
+        &[
+            "show",
+            "--no-pager",
+            "--property=Id,LoadState,ActiveState,SubState,UnitFileState",
+            "--",
+            unit,
+        ],
+        SYSTEMCTL_TIMEOUT_SECS,
+    )
+    .map_err(|e| format!("systemctl show {}: {}", unit, e))?;
+
+    if run.exit_code != Some(0) {
+        return Err(format!(
+            "systemctl show {} failed: {}",
+            unit,
+            run.stderr.trim()
+        ));
+    }
+
+    Ok(run
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect())
+}
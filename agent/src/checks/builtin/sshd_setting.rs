@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use common::{CheckTypeId, ComparisonOperator, SshdSettingParams};
+use serde_json::{json, Value};
+
+use crate::checks::compare::{compare, expectation};
+use crate::checks::runner::run_tool;
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+/// Seconds `sshd -T` may take before the configuration files are parsed instead
+const SSHD_TIMEOUT_SECS: u64 = 10;
+
+/// Nesting limit for Include directives, the same as sshd's
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Directory sshd resolves relative Include paths against
+const SSH_DIR: &str = "/etc/ssh";
+
+/// Keywords whose values add up over several lines instead of the first one winning
+const ACCUMULATING_KEYWORDS: &[&str] = &[
+    "acceptenv",
+    "allowgroups",
+    "allowusers",
+    "denygroups",
+    "denyusers",
+    "hostcertificate",
+    "hostkey",
+    "listenaddress",
+    "port",
+    "subsystem",
+];
+
+/// OpenSSH defaults of commonly checked keywords, used when the configuration
+/// files are parsed and do not set them
+const DEFAULTS: &[(&str, &str)] = &[
+    ("allowagentforwarding", "yes"),
+    ("allowtcpforwarding", "yes"),
+    ("banner", "none"),
+    ("clientalivecountmax", "3"),
+    ("clientaliveinterval", "0"),
+    ("compression", "yes"),
+    ("disableforwarding", "no"),
+    ("gatewayports", "no"),
+    ("gssapiauthentication", "no"),
+    ("hostbasedauthentication", "no"),
+    ("ignorerhosts", "yes"),
+    ("kbdinteractiveauthentication", "yes"),
+    ("logingracetime", "120"),
+    ("loglevel", "INFO"),
+    ("maxauthtries", "6"),
+    ("maxsessions", "10"),
+    ("maxstartups", "10:30:100"),
+    ("passwordauthentication", "yes"),
+    ("permitemptypasswords", "no"),
+    ("permitrootlogin", "prohibit-password"),
+    ("permittunnel", "no"),
+    ("permituserenvironment", "no"),
+    ("port", "22"),
+    ("pubkeyauthentication", "yes"),
+    ("strictmodes", "yes"),
+    ("tcpkeepalive", "yes"),
+    ("usedns", "no"),
+    ("usepam", "no"),
+    ("x11forwarding", "no"),
+];
+
+pub struct SshdSetting;
+
+impl Check for SshdSetting {
+    type Params = SshdSettingParams;
+    const TYPE: CheckTypeId = CheckTypeId::SshdSetting;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux, Platform::MacOs]
+    }
+
+    fn execute(&self, params: &SshdSettingParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let key = params.key.to_ascii_lowercase();
+
+        let mut evidence = json!({
+            "key": params.key,
+            "config_file": params.config_file,
+            "operator": params.operator,
+            "expected": params.expected,
+        });
+
+        let mut is_default = false;
+        let settings = match effective_config(&params.config_file) {
+            Ok(settings) => {
+                evidence["source"] = json!("sshd -T");
+                settings
+            }
+            Err(reason) => {
+                tracing::debug!("sshd -T unavailable, parsing configuration: {}", reason);
+                let mut settings = BTreeMap::new();
+                let mut files = Vec::new();
+                if let Err(e) =
+                    parse_config(Path::new(&params.config_file), &mut settings, &mut files, 0)
+                {
+                    return CheckExecutionResult::error(e).with_evidence(evidence);
+                }
+                if !settings.contains_key(&key) {
+                    if let Some((_, value)) = DEFAULTS.iter().find(|(k, _)| *k == key) {
+                        settings.insert(key.clone(), vec![value.to_string()]);
+                        is_default = true;
+                    }
+                }
+                evidence["source"] = json!("config");
+                evidence["sshd_error"] = json!(reason);
+                evidence["files"] = json!(files);
+                settings
+            }
+        };
+
+        let values = settings.get(&key).cloned().unwrap_or_default();
+        evidence["observed"] = json!(values);
+        evidence["default"] = json!(is_default);
+
+        let expected = expectation(params.operator, params.expected.as_ref());
+        let shown = format!(
+            "{} {}{}",
+            params.key,
+            values.join(", "),
+            if is_default { " (default)" } else { "" }
+        );
+
+        // sshd -T lowercases most values and the parsed files keep their case,
+        // so values compare case-insensitively either way
+        let operand = params
+            .expected
+            .as_ref()
+            .map(|expected| fold_case(params.operator, expected));
+
+        let result = if values.is_empty() {
+            match compare(params.operator, None, operand.as_ref(), params.if_missing) {
+                Ok(true) => {
+                    CheckExecutionResult::pass(Some(format!("sshd {} is not set", params.key)))
+                }
+                Ok(false) => CheckExecutionResult::fail(format!("sshd {} is not set", params.key)),
+                Err(e) => CheckExecutionResult::error(format!("sshd {}: {}", params.key, e)),
+            }
+        } else {
+            // Keywords with several values, such as Port, must pass for each
+            let outcome = values.iter().try_fold(true, |all, value| {
+                compare(
+                    params.operator,
+                    Some(&Value::String(value.to_lowercase())),
+                    operand.as_ref(),
+                    params.if_missing,
+                )
+                .map(|ok| all && ok)
+            });
+            match outcome {
+                Ok(true) => {
+                    CheckExecutionResult::pass(Some(format!("sshd {} ({})", shown, expected)))
+                }
+                Ok(false) => {
+                    CheckExecutionResult::fail(format!("sshd {} (expected {})", shown, expected))
+                }
+                Err(e) => CheckExecutionResult::error(format!("sshd {}: {}", params.key, e)),
+            }
+        };
+        result.with_evidence(evidence)
+    }
+}
+
+/// The operand with its text lowercased, or for `regex` made case-insensitive
+fn fold_case(operator: ComparisonOperator, expected: &Value) -> Value {
+    match (operator, expected) {
+        (ComparisonOperator::Regex, Value::String(pattern)) => {
+            Value::String(format!("(?i){}", pattern))
+        }
+        (_, Value::String(text)) => Value::String(text.to_lowercase()),
+        (_, Value::Array(items)) => {
+            Value::Array(items.iter().map(|item| fold_case(operator, item)).collect())
+        }
+        _ => expected.clone(),
+    }
+}
+
+/// Effective global configuration reported by `sshd -T`, keyed by lowercase keyword
+fn effective_config(config_file: &str) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let run = run_tool("sshd", &["-T", "-f", config_file], SSHD_TIMEOUT_SECS)
+        .map_err(|e| format!("sshd -T: {}", e))?;
+    if run.exit_code != Some(0) {
+        return Err(format!(
+            "sshd -T exited with {}: {}",
+            run.exit_code
+                .map_or_else(|| "a signal".to_string(), |c| format!("code {}", c)),
+            run.stderr.trim()
+        ));
+    }
+
+    let mut settings: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for line in run.stdout.lines() {
+        let (keyword, value) = split_line(line);
+        if !keyword.is_empty() {
+            settings
+                .entry(keyword.to_ascii_lowercase())
+                .or_default()
+                .push(value.to_string());
+        }
+    }
+    Ok(settings)
+}
+
+/// Read the global settings of an sshd_config file, following Include
+/// directives.
+///
+/// As in sshd the first value of a keyword wins, except for keywords that
+/// accumulate. A `Match` block lasts until the end of the file it starts in
+/// and is skipped, since only the global configuration is checked. Relative
+/// Include paths are resolved against /etc/ssh, as sshd does.
+fn parse_config(
+    path: &Path,
+    settings: &mut BTreeMap<String, Vec<String>>,
+    files: &mut Vec<String>,
+    depth: usize,
+) -> Result<(), String> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(format!(
+            "Include nested more than {} levels at {}",
+            MAX_INCLUDE_DEPTH,
+            path.display()
+        ));
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    files.push(path.display().to_string());
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, value) = split_line(line);
+        let keyword = keyword.to_ascii_lowercase();
+        match keyword.as_str() {
+            "match" => break,
+            "include" => {
+                for pattern in value.split_whitespace() {
+                    let pattern = Path::new(SSH_DIR).join(pattern.trim_matches('"'));
+                    let pattern = pattern.to_string_lossy();
+                    let paths = glob::glob(&pattern)
+                        .map_err(|e| format!("Invalid Include pattern {}: {}", pattern, e))?;
+                    for included in paths.flatten() {
+                        parse_config(&included, settings, files, depth + 1)?;
+                    }
+                }
+            }
+            _ => {
+                let value = value.trim_matches('"').to_string();
+                let values = settings.entry(keyword.clone()).or_default();
+                if values.is_empty() || ACCUMULATING_KEYWORDS.contains(&keyword.as_str()) {
+                    values.push(value);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Split a line into its keyword and value, separated by whitespace or `=`
+fn split_line(line: &str) -> (&str, &str) {
+    let line = line.trim();
+    match line.find(|c: char| c.is_whitespace() || c == '=') {
+        Some(i) => {
+            let value = line[i..].trim_start();
+            let value = value.strip_prefix('=').unwrap_or(value).trim();
+            (&line[..i], value)
+        }
+        None => (line, ""),
+    }
+}
@@ -0,0 +1,158 @@
+use std::fs;
+
+use common::{key_path_segments, CheckTypeId, ConfigFormat, StructuredConfigParams};
+use serde_json::{json, Map, Value};
+
+use crate::checks::compare::{compare, expectation};
+use crate::checks::{Check, CheckContext, CheckExecutionResult};
+
+pub struct StructuredConfig;
+
+impl Check for StructuredConfig {
+    type Params = StructuredConfigParams;
+    const TYPE: CheckTypeId = CheckTypeId::StructuredConfig;
+
+    fn execute(
+        &self,
+        params: &StructuredConfigParams,
+        _ctx: &CheckContext,
+    ) -> CheckExecutionResult {
+        let Some(format) = params
+            .format
+            .or_else(|| ConfigFormat::from_path(&params.file))
+        else {
+            return CheckExecutionResult::error(format!(
+                "Cannot tell the format of {}; set format",
+                params.file
+            ));
+        };
+        let segments = match key_path_segments(&params.key) {
+            Ok(s) => s,
+            Err(e) => return CheckExecutionResult::error(e),
+        };
+
+        let content = match fs::read_to_string(&params.file) {
+            Ok(c) => c,
+            Err(e) => {
+                return CheckExecutionResult::error(format!(
+                    "Failed to read config file {}: {}",
+                    params.file, e
+                ))
+            }
+        };
+        let document = match parse(format, &content) {
+            Ok(d) => d,
+            Err(e) => {
+                return CheckExecutionResult::error(format!(
+                    "Failed to parse {} as {}: {}",
+                    params.file, format, e
+                ))
+            }
+        };
+
+        let observed = lookup(&document, &segments);
+        let evidence = json!({
+            "file": params.file,
+            "format": format,
+            "key": params.key,
+            "operator": params.operator,
+            "expected": params.value,
+            "found": observed.is_some(),
+            "observed": observed,
+        });
+
+        let expected = expectation(params.operator, params.value.as_ref());
+        let result = match compare(
+            params.operator,
+            observed,
+            params.value.as_ref(),
+            params.if_missing,
+        ) {
+            Ok(true) => CheckExecutionResult::pass(Some(match observed {
+                Some(value) => format!("{} = {} ({})", params.key, value, expected),
+                None => format!("{} is not set", params.key),
+            })),
+            Ok(false) => CheckExecutionResult::fail(match observed {
+                Some(value) => format!("{} = {} (expected {})", params.key, value, expected),
+                None => format!("{} not found in {}", params.key, params.file),
+            }),
+            Err(e) => CheckExecutionResult::error(format!("{}: {}", params.key, e)),
+        };
+        result.with_evidence(evidence)
+    }
+}
+
+fn parse(format: ConfigFormat, content: &str) -> Result<Value, String> {
+    match format {
+        ConfigFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+        ConfigFormat::Ini => Ok(parse_ini(content)),
+    }
+}
+
+/// Parse an INI file into an object of sections.
+///
+/// Keys before the first section are at the top level. Lines starting with
+/// `;` or `#` are comments, values may be quoted, and a key set twice in the
+/// same section keeps its last value.
+fn parse_ini(content: &str) -> Value {
+    let mut root = Map::new();
+    let mut section: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.trim().to_string();
+            root.entry(name.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            section = Some(name);
+            continue;
+        }
+
+        let (key, value) = match line.find(['=', ':']) {
+            Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+            None => (line, ""),
+        };
+        let value = unquote(value);
+
+        let target = match &section {
+            Some(name) => match root.get_mut(name) {
+                Some(Value::Object(map)) => map,
+                _ => continue,
+            },
+            None => &mut root,
+        };
+        target.insert(key.to_string(), Value::String(value.to_string()));
+    }
+
+    Value::Object(root)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Value at `segments` in `document`; numeric segments index into lists
+fn lookup<'a>(document: &'a Value, segments: &[String]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(document, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+        .filter(|value| !value.is_null())
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{CheckTypeId, MissingKey, SysctlParams};
+use serde_json::{json, Value};
+
+use crate::checks::compare::{compare, expectation};
+use crate::checks::dropin::conf_files;
+use crate::checks::{Check, CheckContext, CheckExecutionResult, Platform};
+
+const PROC_SYS: &str = "/proc/sys";
+
+/// Directories `sysctl --system` reads, in order of precedence
+const SYSCTL_DIRS: &[&str] = &[
+    "/etc/sysctl.d",
+    "/run/sysctl.d",
+    "/usr/local/lib/sysctl.d",
+    "/usr/lib/sysctl.d",
+    "/lib/sysctl.d",
+];
+
+/// Read after the directories, so its settings win
+const SYSCTL_CONF: &str = "/etc/sysctl.conf";
+
+pub struct Sysctl;
+
+impl Check for Sysctl {
+    type Params = SysctlParams;
+    const TYPE: CheckTypeId = CheckTypeId::Sysctl;
+
+    fn platforms(&self) -> &'static [Platform] {
+        &[Platform::Linux]
+    }
+
+    fn execute(&self, params: &SysctlParams, _ctx: &CheckContext) -> CheckExecutionResult {
+        let name = params.key.replace('/', ".");
+        let path = Path::new(PROC_SYS).join(params.key.replace('.', "/"));
+
+        let runtime = match fs::read_to_string(&path) {
+            Ok(content) => Some(normalize(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return CheckExecutionResult::error(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                ))
+            }
+        };
+
+        let mut evidence = json!({
+            "key": name,
+            "operator": params.operator,
+            "expected": params.expected,
+            "runtime": runtime,
+        });
+
+        let expected = expectation(params.operator, params.expected.as_ref());
+        let mut problems = Vec::new();
+        let mut found = Vec::new();
+
+        match compare(
+            params.operator,
+            runtime.clone().map(Value::String).as_ref(),
+            params.expected.as_ref(),
+            params.if_missing,
+        ) {
+            Ok(true) => found.push(match &runtime {
+                Some(value) => format!("{} = {}", name, value),
+                None => format!("{} does not exist", name),
+            }),
+            Ok(false) => problems.push(match &runtime {
+                Some(value) => format!("{} = {} (expected {})", name, value, expected),
+                None => format!("{} does not exist", name),
+            }),
+            Err(e) => {
+                return CheckExecutionResult::error(format!("{}: {}", name, e))
+                    .with_evidence(evidence)
+            }
+        }
+
+        if params.persistent {
+            let (settings, files) = persistent_settings();
+            let configured = settings.get(&name);
+            evidence["persistent"] = json!(configured.map(|(value, _)| value));
+            evidence["persistent_file"] = json!(configured.map(|(_, file)| file));
+            evidence["files"] = json!(files);
+
+            // A parameter the configuration does not set is lost at reboot,
+            // so if_missing only applies at runtime
+            match compare(
+                params.operator,
+                configured
+                    .map(|(value, _)| Value::String(value.clone()))
+                    .as_ref(),
+                params.expected.as_ref(),
+                MissingKey::Fail,
+            ) {
+                Ok(true) => {
+                    if let Some((value, file)) = configured {
+                        found.push(format!("{} sets {}", file, value));
+                    }
+                }
+                Ok(false) => problems.push(match configured {
+                    Some((value, file)) => {
+                        format!("{} sets {} (expected {})", file, value, expected)
+                    }
+                    None => format!("{} is not set in the sysctl configuration", name),
+                }),
+                Err(e) => {
+                    return CheckExecutionResult::error(format!(
+                        "{} in the sysctl configuration: {}",
+                        name, e
+                    ))
+                    .with_evidence(evidence)
+                }
+            }
+        }
+
+        let result = if problems.is_empty() {
+            CheckExecutionResult::pass(Some(format!("{} ({})", found.join("; "), expected)))
+        } else {
+            CheckExecutionResult::fail(problems.join("; "))
+        };
+        result.with_evidence(evidence)
+    }
+}
+
+/// Kernel parameters with several values separate them with tabs
+fn normalize(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Settings the sysctl configuration applies at boot, with the file that
+/// sets each one, and the files read in order.
+///
+/// Files from all directories are read in order of their names, followed by
+/// /etc/sysctl.conf, and the last assignment of a parameter wins.
+fn persistent_settings() -> (BTreeMap<String, (String, String)>, Vec<String>) {
+    let mut paths = conf_files(SYSCTL_DIRS);
+    paths.push(PathBuf::from(SYSCTL_CONF));
+
+    let mut settings = BTreeMap::new();
+    let mut files = Vec::new();
+    for path in paths {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let file = path.display().to_string();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            // A leading "-" only tells sysctl to ignore errors setting the key
+            let key = key.trim().trim_start_matches('-').replace('/', ".");
+            settings.insert(key, (normalize(value), file.clone()));
+        }
+        files.push(file);
+    }
+    (settings, files)
+}
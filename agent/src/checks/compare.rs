@@ -0,0 +1,187 @@
+//! Comparison of observed configuration values with expected ones
+
+use std::cmp::Ordering;
+
+use common::{ComparisonOperator, MissingKey};
+use regex::Regex;
+use serde_json::Value;
+
+/// Value as text for comparisons, or `None` for lists, objects and null
+pub fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn value_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Describe what a comparison expects, such as `ge 4` or `absent`
+pub fn expectation(operator: ComparisonOperator, expected: Option<&Value>) -> String {
+    match expected {
+        Some(value) => format!("{} {}", operator, value),
+        None => operator.to_string(),
+    }
+}
+
+/// Compare an observed value with the operand of `operator`.
+///
+/// `observed` is `None` when the key is not set, which passes for `absent`
+/// and otherwise as `if_missing` says. Returns whether the comparison holds,
+/// or why it could not be made.
+pub fn compare(
+    operator: ComparisonOperator,
+    observed: Option<&Value>,
+    expected: Option<&Value>,
+    if_missing: MissingKey,
+) -> Result<bool, String> {
+    let Some(observed) = observed else {
+        return Ok(operator == ComparisonOperator::Absent || if_missing == MissingKey::Pass);
+    };
+
+    let expected = || expected.ok_or_else(|| format!("{} requires a value", operator));
+    let text = || value_text(observed).ok_or_else(|| format!("{} is not a single value", observed));
+    let number =
+        |value: &Value| value_number(value).ok_or_else(|| format!("{} is not a number", value));
+    let list = || {
+        expected()?
+            .as_array()
+            .ok_or_else(|| format!("{} requires a list of values", operator))
+    };
+
+    match operator {
+        ComparisonOperator::Equals => Ok(Some(text()?) == value_text(expected()?)),
+        ComparisonOperator::NotEquals => Ok(Some(text()?) != value_text(expected()?)),
+        ComparisonOperator::Lt
+        | ComparisonOperator::Le
+        | ComparisonOperator::Gt
+        | ComparisonOperator::Ge => {
+            let ordering = number(observed)?
+                .partial_cmp(&number(expected()?)?)
+                .ok_or("values cannot be ordered")?;
+            Ok(ordering_holds(operator, ordering))
+        }
+        ComparisonOperator::Between => {
+            let bounds = list()?;
+            let [min, max] = bounds.as_slice() else {
+                return Err("between requires a [min, max] list".to_string());
+            };
+            let observed = number(observed)?;
+            Ok(number(min)? <= observed && observed <= number(max)?)
+        }
+        ComparisonOperator::In | ComparisonOperator::NotIn => {
+            let text = text()?;
+            let found = list()?
+                .iter()
+                .any(|item| value_text(item).as_ref() == Some(&text));
+            Ok(found == (operator == ComparisonOperator::In))
+        }
+        ComparisonOperator::Regex => {
+            let pattern = expected()?
+                .as_str()
+                .ok_or("regex requires a pattern string")?;
+            let regex = Regex::new(pattern).map_err(|e| format!("invalid regex: {}", e))?;
+            Ok(regex.is_match(&text()?))
+        }
+        ComparisonOperator::VersionEq
+        | ComparisonOperator::VersionLt
+        | ComparisonOperator::VersionLe
+        | ComparisonOperator::VersionGt
+        | ComparisonOperator::VersionGe => {
+            let expected = expected()?;
+            let expected =
+                value_text(expected).ok_or_else(|| format!("{} is not a version", expected))?;
+            Ok(ordering_holds(
+                operator,
+                compare_versions(&text()?, &expected),
+            ))
+        }
+        ComparisonOperator::Absent => Ok(false),
+    }
+}
+
+/// Whether `ordering` of the observed value against the operand satisfies `operator`
+pub fn ordering_holds(operator: ComparisonOperator, ordering: Ordering) -> bool {
+    match operator {
+        ComparisonOperator::NotEquals => ordering.is_ne(),
+        ComparisonOperator::Lt | ComparisonOperator::VersionLt => ordering.is_lt(),
+        ComparisonOperator::Le | ComparisonOperator::VersionLe => ordering.is_le(),
+        ComparisonOperator::Gt | ComparisonOperator::VersionGt => ordering.is_gt(),
+        ComparisonOperator::Ge | ComparisonOperator::VersionGe => ordering.is_ge(),
+        _ => ordering.is_eq(),
+    }
+}
+
+/// Order two version strings the way semantic versioning does.
+///
+/// A leading `v` and `+build` metadata are ignored and missing components
+/// count as zero, so `v1.2` equals `1.2.0`. Numeric components compare as
+/// numbers and others as text, and a `-pre` release sorts before the release.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (&str, Option<&str>) {
+        let version = version.trim();
+        let version = version
+            .strip_prefix(['v', 'V'])
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(version);
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        match version.split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (version, None),
+        }
+    }
+
+    fn component(a: &str, b: &str) -> Ordering {
+        match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => a.cmp(b),
+        }
+    }
+
+    let (a_release, a_pre) = split(a);
+    let (b_release, b_pre) = split(b);
+
+    let a_parts: Vec<&str> = a_release.split('.').collect();
+    let b_parts: Vec<&str> = b_release.split('.').collect();
+    for i in 0..a_parts.len().max(b_parts.len()) {
+        let a = a_parts.get(i).copied().unwrap_or("0");
+        let b = b_parts.get(i).copied().unwrap_or("0");
+        let ordering = component(a, b);
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let mut a_parts = a.split('.');
+            let mut b_parts = b.split('.');
+            loop {
+                match (a_parts.next(), b_parts.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(a), Some(b)) => {
+                        let ordering = component(a, b);
+                        if ordering.is_ne() {
+                            return ordering;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Configuration split over drop-in directories, such as sysctl.d and modprobe.d
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// The `.conf` files in `dirs`, in order of their names.
+///
+/// `dirs` are listed in order of precedence: a file shadows files of the same
+/// name in later directories. Missing directories are skipped.
+pub fn conf_files(dirs: &[&str]) -> Vec<PathBuf> {
+    let mut by_name: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.ends_with(".conf") {
+                by_name.entry(file_name).or_insert_with(|| entry.path());
+            }
+        }
+    }
+    by_name.into_values().collect()
+}
@@ -0,0 +1,66 @@
+//! Helpers for building the evidence attached to check results
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Value};
+
+/// Characters of text, such as command output, kept in evidence
+pub const EVIDENCE_TEXT_CHARS: usize = 4096;
+
+/// Lines of a file kept when listing matches
+pub const EVIDENCE_MAX_LINES: usize = 20;
+
+/// Metadata of a file, or `null` if it cannot be read
+pub fn file_metadata(path: &Path) -> Value {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Value::Null;
+    };
+
+    let mut value = json!({
+        "size": metadata.len(),
+        "is_dir": metadata.is_dir(),
+        "modified": metadata.modified().ok().map(DateTime::<Utc>::from),
+    });
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        value["mode"] = json!(format!("{:04o}", metadata.mode() & 0o7777));
+        value["uid"] = json!(metadata.uid());
+        value["gid"] = json!(metadata.gid());
+    }
+
+    value
+}
+
+/// Text shortened to [`EVIDENCE_TEXT_CHARS`]
+pub fn text_excerpt(text: &str) -> String {
+    text.chars().take(EVIDENCE_TEXT_CHARS).collect()
+}
+
+/// Lines of `content` on which `regex` matches, with 1-based line numbers
+pub fn matched_lines(content: &str, regex: &Regex) -> Vec<Value> {
+    let mut lines = Vec::new();
+    let mut last_line = 0;
+
+    for m in regex.find_iter(content) {
+        let start = content[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+        let line = content[..start].matches('\n').count() + 1;
+        if line == last_line {
+            continue;
+        }
+        last_line = line;
+
+        let end = content[start..]
+            .find('\n')
+            .map_or(content.len(), |i| start + i);
+        lines.push(json!({ "line": line, "text": text_excerpt(content[start..end].trim_end()) }));
+        if lines.len() == EVIDENCE_MAX_LINES {
+            break;
+        }
+    }
+
+    lines
+}
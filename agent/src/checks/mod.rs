@@ -1,5 +1,17 @@
+#[cfg(unix)]
+mod accounts;
+#[cfg(feature = "command-checks")]
+mod allowlist;
+mod builtin;
+mod compare;
+mod dropin;
+mod evidence;
 mod executor;
+mod packages;
+mod registry;
+mod runner;
 mod types;
 
 pub use executor::*;
+pub use registry::*;
 pub use types::*;
@@ -0,0 +1,410 @@
+//! Installed package lookups in the dpkg and RPM databases, with each
+//! ecosystem's version ordering
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+use common::PackageManager;
+
+use crate::checks::runner::run_tool;
+
+/// Status database dpkg keeps for installed packages
+pub const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+
+/// Locations of the RPM database, the second used since RPM 4.16
+const RPM_DATABASES: &[&str] = &["/var/lib/rpm", "/usr/lib/sysimage/rpm"];
+
+const RPM_TIMEOUT_SECS: u64 = 30;
+
+/// Package manager whose database exists on this host, dpkg first
+pub fn detect_manager() -> Option<PackageManager> {
+    if Path::new(DPKG_STATUS).is_file() {
+        Some(PackageManager::Dpkg)
+    } else if RPM_DATABASES.iter().any(|dir| Path::new(dir).is_dir()) {
+        Some(PackageManager::Rpm)
+    } else {
+        None
+    }
+}
+
+/// Versions of `name` that are installed, one per architecture or instance
+pub fn installed_versions(manager: PackageManager, name: &str) -> Result<Vec<String>, String> {
+    match manager {
+        PackageManager::Dpkg => {
+            let status = fs::read_to_string(DPKG_STATUS)
+                .map_err(|e| format!("Failed to read {}: {}", DPKG_STATUS, e))?;
+            Ok(dpkg_installed(&status, name))
+        }
+        PackageManager::Rpm => rpm_installed(name),
+    }
+}
+
+/// Versions of `name` in a dpkg status file whose state is installed.
+///
+/// A `name:arch` qualifier only matches that architecture.
+fn dpkg_installed(status: &str, name: &str) -> Vec<String> {
+    let (name, arch) = match name.split_once(':') {
+        Some((name, arch)) => (name, Some(arch)),
+        None => (name, None),
+    };
+
+    let mut versions = Vec::new();
+    for stanza in status.split("\n\n") {
+        let field = |wanted: &str| {
+            stanza.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key == wanted).then(|| value.trim())
+            })
+        };
+
+        // Status is "want flag state"; removed packages keep their config files
+        // in the database as "config-files"
+        let installed = field("Status")
+            .and_then(|s| s.split_whitespace().nth(2))
+            .is_some_and(|state| state == "installed");
+        if field("Package") != Some(name) || !installed {
+            continue;
+        }
+        if arch.is_some_and(|arch| field("Architecture") != Some(arch)) {
+            continue;
+        }
+        if let Some(version) = field("Version") {
+            versions.push(version.to_string());
+        }
+    }
+    versions
+}
+
+fn rpm_installed(name: &str) -> Result<Vec<String>, String> {
+    let run = run_tool(
+        "rpm",
+        &[
+            "-q",
+            "--qf",
+            "%{EPOCH}:%{VERSION}-%{RELEASE}\\n",
+            "--",
+            name,
+        ],
+        RPM_TIMEOUT_SECS,
+    )
+    .map_err(|e| format!("rpm -q {}: {}", name, e))?;
+
+    // rpm -q exits with 1 and says so on stdout when the package is not installed
+    if run.exit_code != Some(0) {
+        if run.stdout.contains("is not installed") {
+            return Ok(Vec::new());
+        }
+        return Err(format!("rpm -q {} failed: {}", name, run.stderr.trim()));
+    }
+
+    Ok(run
+        .stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.strip_prefix("(none):").unwrap_or(line).to_string())
+        .collect())
+}
+
+/// Order an installed version against an expected one by the rules of `manager`.
+///
+/// Versions are `[epoch:]version[-release]`. A missing epoch counts as 0. A
+/// release left out of `expected` is not compared, so `3.0.7` matches every
+/// build of 3.0.7.
+pub fn compare_package_versions(
+    manager: PackageManager,
+    installed: &str,
+    expected: &str,
+) -> Ordering {
+    let segment_cmp = match manager {
+        PackageManager::Dpkg => dpkg_verrevcmp,
+        PackageManager::Rpm => rpmvercmp,
+    };
+    let installed = PackageVersion::parse(installed);
+    let expected = PackageVersion::parse(expected);
+
+    installed
+        .epoch
+        .unwrap_or(0)
+        .cmp(&expected.epoch.unwrap_or(0))
+        .then_with(|| segment_cmp(installed.version, expected.version))
+        .then_with(|| match expected.release {
+            Some(release) => segment_cmp(installed.release.unwrap_or(""), release),
+            None => Ordering::Equal,
+        })
+}
+
+struct PackageVersion<'a> {
+    epoch: Option<u64>,
+    version: &'a str,
+    release: Option<&'a str>,
+}
+
+impl<'a> PackageVersion<'a> {
+    /// The epoch ends at the first colon and the release starts after the last hyphen
+    fn parse(text: &'a str) -> Self {
+        let text = text.trim();
+        let (epoch, rest) = match text.split_once(':') {
+            Some((epoch, rest)) if epoch.chars().all(|c| c.is_ascii_digit()) => {
+                (epoch.parse().ok(), rest)
+            }
+            _ => (None, text),
+        };
+        let (version, release) = match rest.rsplit_once('-') {
+            Some((version, release)) => (version, Some(release)),
+            None => (rest, None),
+        };
+        PackageVersion {
+            epoch,
+            version,
+            release,
+        }
+    }
+}
+
+/// Compare version strings as dpkg does.
+///
+/// Runs of non-digits compare character by character with letters before
+/// other characters and `~` before everything, even the end of the string.
+/// Runs of digits compare as numbers.
+fn dpkg_verrevcmp(a: &str, b: &str) -> Ordering {
+    fn order(c: Option<u8>) -> i32 {
+        match c {
+            None => 0,
+            Some(b'~') => -1,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_ascii_alphabetic() => i32::from(c),
+            Some(c) => i32::from(c) + 256,
+        }
+    }
+
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let is_digit = |s: &[u8], i: usize| s.get(i).is_some_and(u8::is_ascii_digit);
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !is_digit(a, i)) || (j < b.len() && !is_digit(b, j)) {
+            let (ac, bc) = (order(a.get(i).copied()), order(b.get(j).copied()));
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        let mut first_diff = Ordering::Equal;
+        while is_digit(a, i) && is_digit(b, j) {
+            if first_diff.is_eq() {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if is_digit(a, i) {
+            return Ordering::Greater;
+        }
+        if is_digit(b, j) {
+            return Ordering::Less;
+        }
+        if first_diff.is_ne() {
+            return first_diff;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Compare version strings as rpmvercmp does.
+///
+/// Separators only split segments. Numeric segments compare as numbers and
+/// are newer than alphabetic ones, `~` sorts before anything and `^` after
+/// the end of the string but before anything else.
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let is_separator = |c: &u8| !c.is_ascii_alphanumeric() && *c != b'~' && *c != b'^';
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+
+    while !a.is_empty() || !b.is_empty() {
+        while a.first().is_some_and(is_separator) {
+            a = &a[1..];
+        }
+        while b.first().is_some_and(is_separator) {
+            b = &b[1..];
+        }
+
+        if a.first() == Some(&b'~') || b.first() == Some(&b'~') {
+            if a.first() != Some(&b'~') {
+                return Ordering::Greater;
+            }
+            if b.first() != Some(&b'~') {
+                return Ordering::Less;
+            }
+            a = &a[1..];
+            b = &b[1..];
+            continue;
+        }
+
+        if a.first() == Some(&b'^') || b.first() == Some(&b'^') {
+            if a.is_empty() {
+                return Ordering::Less;
+            }
+            if b.is_empty() {
+                return Ordering::Greater;
+            }
+            if a.first() != Some(&b'^') {
+                return Ordering::Greater;
+            }
+            if b.first() != Some(&b'^') {
+                return Ordering::Less;
+            }
+            a = &a[1..];
+            b = &b[1..];
+            continue;
+        }
+
+        if a.is_empty() || b.is_empty() {
+            break;
+        }
+
+        let numeric = a[0].is_ascii_digit();
+        let in_segment = |c: &u8| {
+            if numeric {
+                c.is_ascii_digit()
+            } else {
+                c.is_ascii_alphabetic()
+            }
+        };
+        let a_len = a.iter().take_while(|c| in_segment(c)).count();
+        let b_len = b.iter().take_while(|c| in_segment(c)).count();
+        let (a_segment, b_segment) = (&a[..a_len], &b[..b_len]);
+        a = &a[a_len..];
+        b = &b[b_len..];
+
+        // Segments of different types: numbers are newer
+        if b_segment.is_empty() {
+            return if numeric {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let ordering = if numeric {
+            let trim = |s: &'_ [u8]| -> usize { s.iter().take_while(|c| **c == b'0').count() };
+            let a_segment = &a_segment[trim(a_segment)..];
+            let b_segment = &b_segment[trim(b_segment)..];
+            a_segment
+                .len()
+                .cmp(&b_segment.len())
+                .then_with(|| a_segment.cmp(b_segment))
+        } else {
+            a_segment.cmp(b_segment)
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dpkg_ordering() {
+        let cases = [
+            ("1.0~rc1", "1.0", Ordering::Less),
+            ("1.0~~", "1.0~", Ordering::Less),
+            ("1.02", "1.2", Ordering::Equal),
+            ("001", "1", Ordering::Equal),
+            ("10", "9", Ordering::Greater),
+            ("1.0a", "1.0", Ordering::Greater),
+            ("1.0", "1.0.1", Ordering::Less),
+            ("1.0+b1", "1.0a", Ordering::Greater),
+            ("2.30", "2.4", Ordering::Greater),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(dpkg_verrevcmp(a, b), expected, "{} vs {}", a, b);
+            assert_eq!(dpkg_verrevcmp(b, a), expected.reverse(), "{} vs {}", b, a);
+        }
+    }
+
+    #[test]
+    fn rpm_ordering() {
+        let cases = [
+            ("1.0~rc1", "1.0", Ordering::Less),
+            ("1.0~rc1", "1.0~rc2", Ordering::Less),
+            ("1.0^git1", "1.0", Ordering::Greater),
+            ("1.0^git1", "1.0.1", Ordering::Less),
+            ("1.0a", "1.0.1", Ordering::Less),
+            ("1.02", "1.2", Ordering::Equal),
+            ("1.0", "1_0", Ordering::Equal),
+            ("2.0", "10", Ordering::Less),
+            ("1.0", "1.0a", Ordering::Less),
+            ("a", "1", Ordering::Less),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(rpmvercmp(a, b), expected, "{} vs {}", a, b);
+            assert_eq!(rpmvercmp(b, a), expected.reverse(), "{} vs {}", b, a);
+        }
+    }
+
+    #[test]
+    fn package_version_ordering() {
+        let cases = [
+            ("1:0.9", "2.0", Ordering::Greater),
+            ("0.9", "1:0.1", Ordering::Less),
+            ("0:1.0", "1.0", Ordering::Equal),
+            ("2:1.0", "10:1.0", Ordering::Less),
+            ("1.0-2", "1.0", Ordering::Equal),
+            ("1.0-2", "1.0-10", Ordering::Less),
+            ("1.2-3-4", "1.2-3-5", Ordering::Less),
+            ("1.2-4-1", "1.2-3-5", Ordering::Greater),
+            (" 3.0.7-1 ", "3.0.7", Ordering::Equal),
+        ];
+        for manager in [PackageManager::Dpkg, PackageManager::Rpm] {
+            for (installed, expected, ordering) in cases {
+                assert_eq!(
+                    compare_package_versions(manager, installed, expected),
+                    ordering,
+                    "{:?}: {} vs {}",
+                    manager,
+                    installed,
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse_package_version() {
+        let version = PackageVersion::parse("1:2.3-4-5");
+        assert_eq!(version.epoch, Some(1));
+        assert_eq!(version.version, "2.3-4");
+        assert_eq!(version.release, Some("5"));
+
+        let version = PackageVersion::parse(" 2.3 ");
+        assert_eq!(version.epoch, None);
+        assert_eq!(version.version, "2.3");
+        assert_eq!(version.release, None);
+
+        let version = PackageVersion::parse("a:1.0");
+        assert_eq!(version.epoch, None);
+        assert_eq!(version.version, "a:1.0");
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use common::signing::{self, VerifyingKey};
+use common::{parse_params, AgentCheckDefinition, CheckTypeId};
+use serde::de::DeserializeOwned;
+use sysinfo::System;
+use validator::Validate;
+
+use super::types::CheckExecutionResult;
+
+/// Operating system a check can run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Linux,
+    MacOs,
+    Windows,
+}
+
+impl Platform {
+    pub const ALL: &'static [Platform] = &[Platform::Linux, Platform::MacOs, Platform::Windows];
+
+    pub fn current() -> Option<Platform> {
+        if cfg!(target_os = "linux") {
+            Some(Platform::Linux)
+        } else if cfg!(target_os = "macos") {
+            Some(Platform::MacOs)
+        } else if cfg!(target_os = "windows") {
+            Some(Platform::Windows)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Platform::Linux => write!(f, "Linux"),
+            Platform::MacOs => write!(f, "macOS"),
+            Platform::Windows => write!(f, "Windows"),
+        }
+    }
+}
+
+/// State shared by all checks in a collection cycle
+pub struct CheckContext {
+    pub system: System,
+}
+
+impl CheckContext {
+    pub fn new() -> Self {
+        Self {
+            system: System::new_all(),
+        }
+    }
+
+    /// Refresh system information before running a batch of checks
+    pub fn refresh(&mut self) {
+        self.system.refresh_all();
+    }
+}
+
+impl Default for CheckContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A check type the agent knows how to execute
+pub trait Check: Send + Sync {
+    type Params: DeserializeOwned + Validate;
+
+    /// The check type this implementation handles
+    const TYPE: CheckTypeId;
+
+    /// Platforms the check can run on; the check is skipped elsewhere
+    fn platforms(&self) -> &'static [Platform] {
+        Platform::ALL
+    }
+
+    /// Whether a definition with these parameters may only run with a valid
+    /// server signature
+    fn requires_signature(&self, _params: &Self::Params) -> bool {
+        false
+    }
+
+    fn parse(&self, parameters: &serde_json::Value) -> Result<Self::Params, String> {
+        parse_params(parameters).map_err(|errors| {
+            errors
+                .iter()
+                .map(|e| format!("{}: {}", e.field, e.message))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    }
+
+    fn execute(&self, params: &Self::Params, ctx: &CheckContext) -> CheckExecutionResult;
+}
+
+/// Object-safe wrapper around [`Check`] so checks with different parameter
+/// types can live in one registry
+trait DynCheck: Send + Sync {
+    fn platforms(&self) -> &'static [Platform];
+    fn run(
+        &self,
+        parameters: &serde_json::Value,
+        ctx: &CheckContext,
+        verify_signature: &dyn Fn() -> Result<(), String>,
+    ) -> CheckExecutionResult;
+}
+
+impl<C: Check> DynCheck for C {
+    fn platforms(&self) -> &'static [Platform] {
+        Check::platforms(self)
+    }
+
+    fn run(
+        &self,
+        parameters: &serde_json::Value,
+        ctx: &CheckContext,
+        verify_signature: &dyn Fn() -> Result<(), String>,
+    ) -> CheckExecutionResult {
+        let params = match self.parse(parameters) {
+            Ok(params) => params,
+            Err(e) => return CheckExecutionResult::error(format!("Invalid parameters: {}", e)),
+        };
+        if self.requires_signature(&params) {
+            if let Err(e) = verify_signature() {
+                return CheckExecutionResult::error(e);
+            }
+        }
+        self.execute(&params, ctx)
+    }
+}
+
+/// Registered check implementations, keyed by check type
+#[derive(Default)]
+pub struct CheckRegistry {
+    checks: HashMap<CheckTypeId, Box<dyn DynCheck>>,
+    verifying_key: Option<VerifyingKey>,
+}
+
+impl CheckRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a check implementation, replacing any previous one for the same type
+    pub fn register<C: Check + 'static>(&mut self, check: C) {
+        self.checks.insert(C::TYPE, Box::new(check));
+    }
+
+    /// Verify checks that require a signature against the matching private key
+    pub fn set_verifying_key(&mut self, key: VerifyingKey) {
+        self.verifying_key = Some(key);
+    }
+
+    /// Check types with a registered implementation, sorted by name
+    pub fn types(&self) -> Vec<CheckTypeId> {
+        let mut types: Vec<CheckTypeId> = self.checks.keys().copied().collect();
+        types.sort_by_key(|t| t.to_string());
+        types
+    }
+
+    pub fn execute(
+        &self,
+        check: &AgentCheckDefinition,
+        ctx: &CheckContext,
+    ) -> CheckExecutionResult {
+        // A server newer than this agent may send check types it does not know
+        let Ok(type_id) = check.check_type.parse::<CheckTypeId>() else {
+            return CheckExecutionResult::skipped(format!(
+                "Check type {} is not supported by this agent",
+                check.check_type
+            ));
+        };
+
+        let Some(implementation) = self.checks.get(&type_id) else {
+            return CheckExecutionResult::skipped(format!(
+                "Check type {} is not supported by this agent",
+                type_id
+            ));
+        };
+
+        let platforms = implementation.platforms();
+        if !Platform::current().is_some_and(|p| platforms.contains(&p)) {
+            let names: Vec<String> = platforms.iter().map(|p| p.to_string()).collect();
+            return CheckExecutionResult::skipped(format!(
+                "{} checks are only available on {}",
+                type_id,
+                names.join(", ")
+            ));
+        }
+
+        let verify_signature = || {
+            let Some(key) = &self.verifying_key else {
+                return Err(
+                    "Check must be signed by the server, but no public key is configured"
+                        .to_string(),
+                );
+            };
+            let Some(signature) = &check.signature else {
+                return Err("Check is not signed by the server".to_string());
+            };
+            signing::verify(key, &check.signing_payload(), signature)
+                .map_err(|e| format!("Check signature rejected: {}", e))
+        };
+
+        implementation.run(&check.parameters, ctx, &verify_signature)
+    }
+}
@@ -0,0 +1,360 @@
+//! Process execution for checks that run commands
+
+use std::io::{self, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "command-checks")]
+use common::CommandOutputParams;
+
+/// How often a running command is polled for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long output pipes may stay open after the command's process group is
+/// killed, for processes that left the group but inherited the pipes
+const PIPE_GRACE: Duration = Duration::from_secs(1);
+
+/// Output kept from each stream of a system tool
+const TOOL_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+#[cfg(not(target_os = "windows"))]
+const DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// Variables a Windows shell needs to start, always passed through
+#[cfg(target_os = "windows")]
+const WINDOWS_BASE_ENV: &[&str] = &["SystemRoot", "ComSpec", "PATH", "PATHEXT", "TEMP", "TMP"];
+
+/// Result of a command that ran to completion
+pub struct CommandRun {
+    /// Exit code, `None` if the process was terminated by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Whether stdout or stderr exceeded `max_output_bytes`
+    #[cfg_attr(not(feature = "command-checks"), allow(dead_code))]
+    pub truncated: bool,
+}
+
+#[cfg(feature = "command-checks")]
+impl CommandRun {
+    pub fn combined_output(&self) -> String {
+        format!("{}{}", self.stdout, self.stderr)
+    }
+}
+
+/// Run a shell command with the limits from `params`.
+///
+/// The command gets a cleared environment, its own process group (killed as
+/// a whole on timeout) and at most `max_output_bytes` captured from each of
+/// stdout and stderr. It runs as `run_as_user`/`run_as_group` when set, and
+/// is refused if it would run as root without `allow_root`. Returns an error
+/// message if the command could not be started or did not finish in time.
+#[cfg(feature = "command-checks")]
+pub fn run_shell_command(
+    params: &CommandOutputParams,
+    allow_root: bool,
+) -> Result<CommandRun, String> {
+    let mut command = shell_command(&params.command);
+
+    apply_base_env(&mut command);
+    apply_identity(&mut command, params, allow_root)?;
+    for name in &params.pass_env {
+        if let Ok(value) = std::env::var(name) {
+            command.env(name, value);
+        }
+    }
+    command.envs(&params.env);
+
+    if let Some(dir) = &params.working_dir {
+        command.current_dir(dir);
+    }
+
+    run(command, params.timeout_secs, params.max_output_bytes)
+}
+
+/// Run a system tool the agent itself needs, such as `sshd -T`.
+///
+/// The tool runs with the agent's identity and the same cleared environment
+/// and process group handling as shell commands. Output beyond
+/// `TOOL_OUTPUT_BYTES` is dropped.
+pub fn run_tool(program: &str, args: &[&str], timeout_secs: u64) -> Result<CommandRun, String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    apply_base_env(&mut command);
+
+    run(command, timeout_secs, TOOL_OUTPUT_BYTES)
+}
+
+fn apply_base_env(command: &mut Command) {
+    command.env_clear();
+    #[cfg(not(target_os = "windows"))]
+    command.env("PATH", DEFAULT_PATH);
+    #[cfg(target_os = "windows")]
+    for name in WINDOWS_BASE_ENV {
+        if let Ok(value) = std::env::var(name) {
+            command.env(name, value);
+        }
+    }
+}
+
+fn run(
+    mut command: Command,
+    timeout_secs: u64,
+    max_output_bytes: usize,
+) -> Result<CommandRun, String> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to execute command: {}", e))?;
+
+    let stdout = capture(child.stdout.take(), max_output_bytes);
+    let stderr = capture(child.stderr.take(), max_output_bytes);
+
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+    loop {
+        match has_exited(&mut child) {
+            Ok(true) => break,
+            Ok(false) if Instant::now() >= deadline => {
+                kill_process_group(&mut child);
+                let _ = child.wait();
+                return Err(format!("Command timed out after {} seconds", timeout_secs));
+            }
+            Ok(false) => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                kill_process_group(&mut child);
+                let _ = child.wait();
+                return Err(format!("Failed to wait for command: {}", e));
+            }
+        }
+    }
+
+    // Background processes left behind by the shell would keep the output
+    // pipes open; they are part of the check and go with it. The exited
+    // child is only reaped afterwards, so its process group id cannot have
+    // been reused by then.
+    kill_process_group(&mut child);
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for command: {}", e))?;
+
+    let pipe_deadline = deadline.max(Instant::now() + PIPE_GRACE);
+    let (stdout, stdout_truncated) = collect(stdout, pipe_deadline);
+    let (stderr, stderr_truncated) = collect(stderr, pipe_deadline);
+
+    Ok(CommandRun {
+        exit_code: status.code(),
+        stdout,
+        stderr,
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+/// Switch the command to the requested user and group.
+///
+/// Sets `HOME`, `USER` and `LOGNAME` for the target user; `env` in the
+/// parameters can still override them. On Linux the command also runs with
+/// `no_new_privs`, so setuid binaries cannot raise its privileges again.
+#[cfg(all(unix, feature = "command-checks"))]
+fn apply_identity(
+    command: &mut Command,
+    params: &CommandOutputParams,
+    allow_root: bool,
+) -> Result<(), String> {
+    use std::os::unix::process::CommandExt;
+
+    use super::accounts::{lookup_group, lookup_user};
+
+    let user = match &params.run_as_user {
+        Some(name) => {
+            Some(lookup_user(name)?.ok_or_else(|| format!("Unknown run_as_user: {}", name))?)
+        }
+        None => None,
+    };
+    let gid = match &params.run_as_group {
+        Some(name) => {
+            Some(lookup_group(name)?.ok_or_else(|| format!("Unknown run_as_group: {}", name))?)
+        }
+        None => user.as_ref().map(|u| u.gid),
+    };
+
+    let uid = match &user {
+        Some(user) => user.uid,
+        None => unsafe { libc::geteuid() },
+    };
+    if uid == 0 && !allow_root {
+        return Err(
+            "Refusing to run command as root: set run_as_user on the check or \
+             ALLOW_ROOT_COMMANDS=true on the agent"
+                .to_string(),
+        );
+    }
+
+    if let Some(user) = &user {
+        command
+            .env("HOME", &user.home)
+            .env("USER", &user.name)
+            .env("LOGNAME", &user.name)
+            .uid(user.uid);
+    }
+    if let Some(gid) = gid {
+        command.gid(gid);
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe {
+        command.pre_exec(|| {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(all(not(unix), feature = "command-checks"))]
+fn apply_identity(
+    _command: &mut Command,
+    params: &CommandOutputParams,
+    _allow_root: bool,
+) -> Result<(), String> {
+    if params.run_as_user.is_some() || params.run_as_group.is_some() {
+        return Err("run_as_user and run_as_group are only supported on Unix".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "command-checks")]
+fn shell_command(script: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut command = Command::new("cmd");
+        command.args(["/C", script]);
+        command
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+}
+
+/// Output read from a pipe so far, and whether any of it was dropped
+type Captured = Arc<Mutex<(Vec<u8>, bool)>>;
+
+/// A pipe being read on a separate thread
+struct Capture {
+    output: Captured,
+    /// Disconnected once the pipe is closed
+    done: Receiver<()>,
+}
+
+/// Read a pipe on a separate thread, keeping at most `limit` bytes.
+///
+/// Anything beyond the limit is read and discarded so the child never blocks
+/// on a full pipe.
+fn capture<R: Read + Send + 'static>(pipe: Option<R>, limit: usize) -> Option<Capture> {
+    let mut pipe = pipe?;
+    let output = Captured::default();
+    let (done_tx, done) = mpsc::channel();
+
+    let shared = Arc::clone(&output);
+    thread::spawn(move || {
+        let _done = done_tx;
+        let mut buf = [0u8; 8192];
+
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let mut output = shared.lock().unwrap_or_else(|e| e.into_inner());
+                    let (kept, truncated) = &mut *output;
+                    let room = limit.saturating_sub(kept.len());
+                    if n > room {
+                        *truncated = true;
+                    }
+                    kept.extend_from_slice(&buf[..n.min(room)]);
+                }
+            }
+        }
+    });
+
+    Some(Capture { output, done })
+}
+
+/// Output of a captured pipe once it closes or `deadline` passes.
+///
+/// A process that escaped the process group can hold the pipe open
+/// indefinitely; its reader is then left behind and the output read so far
+/// is returned.
+fn collect(capture: Option<Capture>, deadline: Instant) -> (String, bool) {
+    let Some(capture) = capture else {
+        return (String::new(), false);
+    };
+
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    if let Err(mpsc::RecvTimeoutError::Timeout) = capture.done.recv_timeout(timeout) {
+        tracing::warn!("Command output still open after the command finished; ignoring the rest");
+    }
+
+    let output = capture.output.lock().unwrap_or_else(|e| e.into_inner());
+    (String::from_utf8_lossy(&output.0).into_owned(), output.1)
+}
+
+/// Whether the child has exited, without reaping it.
+///
+/// An unreaped child keeps its pid, and with it the process group id,
+/// reserved until `kill_process_group` has run.
+#[cfg(unix)]
+fn has_exited(child: &mut Child) -> io::Result<bool> {
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::waitid(
+            libc::P_PID,
+            child.id() as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // si_pid stays zero while the child is still running
+    Ok(unsafe { info.si_pid() } != 0)
+}
+
+#[cfg(not(unix))]
+fn has_exited(child: &mut Child) -> io::Result<bool> {
+    child.try_wait().map(|status| status.is_some())
+}
+
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) {
+    // The child leads its own process group, so its pid is the group id
+    let pgid = child.id() as libc::pid_t;
+    unsafe {
+        libc::kill(-pgid, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) {
+    let _ = child.kill();
+}
@@ -1,10 +1,14 @@
-use common::{AgentCheckDefinition, CheckStatus};
-use serde::Deserialize;
+use common::CheckStatus;
+use serde_json::Value;
 
 #[derive(Debug)]
 pub struct CheckExecutionResult {
     pub status: CheckStatus,
     pub message: Option<String>,
+    /// What the check observed and compared against, as a JSON object
+    pub evidence: Option<Value>,
+    /// Measured value for checks that produce a number
+    pub value: Option<f64>,
 }
 
 impl CheckExecutionResult {
@@ -12,6 +16,8 @@ impl CheckExecutionResult {
         Self {
             status: CheckStatus::Pass,
             message,
+            evidence: None,
+            value: None,
         }
     }
 
@@ -19,6 +25,8 @@ impl CheckExecutionResult {
         Self {
             status: CheckStatus::Fail,
             message: Some(message.into()),
+            evidence: None,
+            value: None,
         }
     }
 
@@ -26,6 +34,8 @@ impl CheckExecutionResult {
         Self {
             status: CheckStatus::Error,
             message: Some(message.into()),
+            evidence: None,
+            value: None,
         }
     }
 
@@ -33,53 +43,18 @@ impl CheckExecutionResult {
         Self {
             status: CheckStatus::Skipped,
             message: Some(message.into()),
+            evidence: None,
+            value: None,
         }
     }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct FileExistsParams {
-    pub path: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct FileContentParams {
-    pub path: String,
-    pub pattern: String,
-    #[serde(default = "default_true")]
-    pub should_match: bool,
-}
-
-fn default_true() -> bool {
-    true
-}
-
-#[derive(Debug, Deserialize)]
-pub struct RegistryKeyParams {
-    pub path: String,
-    pub value_name: Option<String>,
-    pub expected: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ConfigSettingParams {
-    pub file: String,
-    pub key: String,
-    pub expected: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ProcessRunningParams {
-    pub name: String,
-}
 
-#[derive(Debug, Deserialize)]
-pub struct PortOpenParams {
-    pub port: u16,
-}
+    pub fn with_evidence(mut self, evidence: Value) -> Self {
+        self.evidence = Some(evidence);
+        self
+    }
 
-#[derive(Debug, Deserialize)]
-pub struct CommandOutputParams {
-    pub command: String,
-    pub expected_pattern: String,
+    pub fn with_value(mut self, value: f64) -> Self {
+        self.value = Some(value);
+        self
+    }
 }
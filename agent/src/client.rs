@@ -1,15 +1,38 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
+
 use anyhow::{Context, Result};
+use chrono::Utc;
+use common::signing::{self, VerifyingKey};
 use common::{
     AgentCheckResult, ChecksResponse, HeartbeatRequest, HeartbeatResponse, RegisterRequest,
-    RegisterResponse, SubmitResultsRequest, SubmitResultsResponse, SystemSnapshotData,
+    RegisterResponse, SubmitFileChangesRequest, SubmitFileChangesResponse, SubmitResultsRequest,
+    SubmitResultsResponse, SystemSnapshotData,
 };
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::state::write_atomic;
+
+/// File in the state directory holding the last accepted bundle revision
+const REVISION_FILE: &str = "bundle_revision.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RevisionState {
+    revision: i64,
+}
+
 pub struct ServerClient {
     client: Client,
     base_url: String,
     agent_secret: String,
+    /// Pinned server key; when set, check bundles must be signed with it
+    verifying_key: Option<VerifyingKey>,
+    /// Highest bundle revision accepted so far, to reject replayed bundles
+    last_revision: AtomicI64,
+    /// Where the accepted revision is persisted across restarts
+    revision_path: Option<PathBuf>,
 }
 
 impl ServerClient {
@@ -23,9 +46,37 @@ impl ServerClient {
             client,
             base_url: base_url.trim_end_matches('/').to_string(),
             agent_secret: agent_secret.to_string(),
+            verifying_key: None,
+            last_revision: AtomicI64::new(i64::MIN),
+            revision_path: None,
         }
     }
 
+    /// Only accept check bundles signed with the matching private key, and
+    /// none older than the revision last accepted according to `state_dir`
+    pub fn with_verifying_key(mut self, key: VerifyingKey, state_dir: &str) -> Self {
+        let path = Path::new(state_dir).join(REVISION_FILE);
+        let revision = match std::fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<RevisionState>(&content) {
+                Ok(state) => Some(state.revision),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid bundle revision {}: {}", path.display(), e);
+                    None
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                tracing::warn!("Failed to read bundle revision {}: {}", path.display(), e);
+                None
+            }
+        };
+
+        self.verifying_key = Some(key);
+        self.last_revision = AtomicI64::new(revision.unwrap_or(i64::MIN));
+        self.revision_path = Some(path);
+        self
+    }
+
     pub async fn register(&self, request: RegisterRequest) -> Result<RegisterResponse> {
         let url = format!("{}/api/agent/register", self.base_url);
 
@@ -100,10 +151,53 @@ impl ServerClient {
             anyhow::bail!("Failed to get checks: {} - {}", status, text);
         }
 
-        response
+        let Some(key) = &self.verifying_key else {
+            return response
+                .json()
+                .await
+                .context("Failed to parse checks response");
+        };
+
+        let value: serde_json::Value = response
             .json()
             .await
-            .context("Failed to parse checks response")
+            .context("Failed to parse checks response")?;
+        let bundle = signing::verify_checks_response(key, value).map_err(anyhow::Error::msg)?;
+
+        if bundle.expires_at <= Utc::now() {
+            anyhow::bail!(
+                "Check bundle revision {} expired at {}",
+                bundle.revision,
+                bundle.expires_at
+            );
+        }
+
+        let last = self.last_revision.load(Ordering::SeqCst);
+        if bundle.revision < last {
+            anyhow::bail!(
+                "Check bundle revision {} is older than accepted revision {}",
+                bundle.revision,
+                last
+            );
+        }
+        if bundle.revision > last {
+            self.last_revision.store(bundle.revision, Ordering::SeqCst);
+            self.save_revision(bundle.revision);
+        }
+
+        Ok(bundle)
+    }
+
+    fn save_revision(&self, revision: i64) {
+        let Some(path) = &self.revision_path else {
+            return;
+        };
+        let saved = serde_json::to_vec(&RevisionState { revision })
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| write_atomic(path, &contents));
+        if let Err(e) = saved {
+            tracing::warn!("Failed to save bundle revision: {:#}", e);
+        }
     }
 
     pub async fn submit_results(
@@ -138,4 +232,31 @@ impl ServerClient {
             .await
             .context("Failed to parse submit results response")
     }
+
+    pub async fn submit_file_changes(
+        &self,
+        request: &SubmitFileChangesRequest,
+    ) -> Result<SubmitFileChangesResponse> {
+        let url = format!("{}/api/agent/file-changes", self.base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("X-Agent-Secret", &self.agent_secret)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to submit file changes")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to submit file changes: {} - {}", status, text);
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse submit file changes response")
+    }
 }
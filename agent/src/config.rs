@@ -8,12 +8,51 @@ pub struct Config {
     pub collection_interval_secs: u64,
     #[serde(default)]
     pub hostname_override: Option<String>,
+    /// Allow command checks to run as root when no `run_as_user` is set
+    #[serde(default)]
+    pub allow_root_commands: bool,
+    /// File listing commands command checks may run without a signature
+    #[serde(default)]
+    pub command_allowlist_file: Option<String>,
+    /// Base64 Ed25519 public key of the server. It also verifies the whole
+    /// check bundle: unsigned or expired bundles and older revisions are
+    /// rejected. Command checks outside the allowlist must be signed by it.
+    #[serde(default)]
+    pub check_signing_public_key: Option<String>,
+    /// Number of checks executed at the same time
+    #[serde(default = "default_max_concurrent_checks")]
+    pub max_concurrent_checks: usize,
+    /// Directory for state kept across restarts, such as when each check last ran
+    #[serde(default = "default_state_dir")]
+    pub state_dir: String,
+    /// Comma-separated directories watched for file changes; monitoring is off when unset
+    #[serde(default)]
+    pub fim_paths: Option<String>,
+    /// Seconds between file integrity scans
+    #[serde(default = "default_fim_interval")]
+    pub fim_interval_secs: u64,
 }
 
 fn default_interval() -> u64 {
     300 // 5 minutes
 }
 
+fn default_max_concurrent_checks() -> usize {
+    4
+}
+
+fn default_fim_interval() -> u64 {
+    3600
+}
+
+fn default_state_dir() -> String {
+    if cfg!(target_os = "windows") {
+        r"C:\ProgramData\Endpoint Assessment Agent".to_string()
+    } else {
+        "/var/lib/endpoint-agent".to_string()
+    }
+}
+
 impl Config {
     pub fn from_env() -> Result<Self, config::ConfigError> {
         let config = config::Config::builder()
@@ -29,6 +68,24 @@ impl Config {
             agent_secret,
             collection_interval_secs: default_interval(),
             hostname_override: None,
+            allow_root_commands: false,
+            command_allowlist_file: None,
+            check_signing_public_key: None,
+            max_concurrent_checks: default_max_concurrent_checks(),
+            state_dir: default_state_dir(),
+            fim_paths: None,
+            fim_interval_secs: default_fim_interval(),
         }
     }
+
+    /// Directories watched for file changes
+    pub fn fim_paths(&self) -> Vec<String> {
+        self.fim_paths
+            .iter()
+            .flat_map(|paths| paths.split(','))
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(String::from)
+            .collect()
+    }
 }
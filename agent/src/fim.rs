@@ -0,0 +1,301 @@
+//! File integrity monitoring.
+//!
+//! The first scan of the configured directories becomes the baseline. Later
+//! scans report every file that differs from it until the change is accepted
+//! on the server, which hands the accepted states back in its response.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::Utc;
+use common::{AcceptedFileChange, FileChange, FileChangeKind, FileState, SubmitFileChangesRequest};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::client::ServerClient;
+use crate::state::write_atomic;
+
+/// File in the state directory holding the baseline
+const BASELINE_FILE: &str = "fim_baseline.json";
+
+/// Most files a scan records; larger trees are refused rather than truncated
+const MAX_MONITORED_FILES: usize = 100_000;
+
+/// Most changes sent in one report
+const MAX_REPORTED_CHANGES: usize = 1000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Baseline {
+    /// Directories the baseline was taken from
+    roots: Vec<String>,
+    files: BTreeMap<String, FileState>,
+    /// Taken since the last report the server received, which must say so
+    #[serde(default)]
+    unreported: bool,
+}
+
+pub struct FileIntegrityMonitor {
+    path: PathBuf,
+    roots: Vec<String>,
+    baseline: Option<Baseline>,
+    /// Accepted changes applied to the baseline but not yet confirmed to the server
+    applied: Vec<Uuid>,
+}
+
+impl FileIntegrityMonitor {
+    /// Load the baseline for `roots` from `state_dir`.
+    ///
+    /// A baseline taken from other directories is discarded, so the next scan
+    /// starts a new one instead of reporting every file as added or removed.
+    pub fn load(state_dir: &str, roots: Vec<String>) -> Self {
+        let path = Path::new(state_dir).join(BASELINE_FILE);
+
+        let baseline = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Baseline>(&content) {
+                Ok(baseline) if baseline.roots == roots => Some(baseline),
+                Ok(_) => {
+                    tracing::info!("Monitored directories changed; taking a new file baseline");
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid file baseline {}: {}", path.display(), e);
+                    None
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => {
+                tracing::warn!("Failed to read file baseline {}: {}", path.display(), e);
+                None
+            }
+        };
+
+        Self {
+            path,
+            roots,
+            baseline,
+            applied: Vec::new(),
+        }
+    }
+
+    /// Scan the monitored directories and report drift from the baseline
+    pub async fn scan_and_report(
+        &mut self,
+        client: &ServerClient,
+        endpoint_id: Uuid,
+    ) -> anyhow::Result<()> {
+        let roots = self.roots.clone();
+        let files = tokio::task::spawn_blocking(move || scan(&roots))
+            .await
+            .context("File integrity scan panicked")??;
+        let scanned_at = Utc::now();
+        let files_scanned = files.len() as u64;
+
+        let mut changes = match &self.baseline {
+            Some(baseline) => diff(&baseline.files, &files),
+            None => {
+                tracing::info!("Recorded file baseline of {} files", files_scanned);
+                self.baseline = Some(Baseline {
+                    roots: self.roots.clone(),
+                    files,
+                    unreported: true,
+                });
+                self.save()?;
+                Vec::new()
+            }
+        };
+        let baseline_reset = self.baseline.as_ref().is_some_and(|b| b.unreported);
+        let truncated = changes.len() > MAX_REPORTED_CHANGES;
+        if truncated {
+            tracing::warn!(
+                "{} files changed; reporting the first {}",
+                changes.len(),
+                MAX_REPORTED_CHANGES
+            );
+            changes.truncate(MAX_REPORTED_CHANGES);
+        }
+
+        let request = SubmitFileChangesRequest {
+            endpoint_id,
+            scanned_at,
+            files_scanned,
+            changes,
+            truncated,
+            applied: self.applied.clone(),
+            baseline_reset,
+        };
+        let response = client.submit_file_changes(&request).await?;
+        self.applied.clear();
+        if let Some(baseline) = self.baseline.as_mut().filter(|b| b.unreported) {
+            baseline.unreported = false;
+            self.save()?;
+        }
+        tracing::info!(
+            "Scanned {} files: {} changed, {} open on the server",
+            request.files_scanned,
+            request.changes.len(),
+            response.open_changes
+        );
+
+        if !response.accepted.is_empty() {
+            tracing::info!(
+                "Applying {} accepted file changes to the baseline",
+                response.accepted.len()
+            );
+            self.accept(&response.accepted)?;
+            self.applied
+                .extend(response.accepted.iter().map(|change| change.id));
+        }
+
+        Ok(())
+    }
+
+    fn accept(&mut self, accepted: &[AcceptedFileChange]) -> anyhow::Result<()> {
+        let Some(baseline) = &mut self.baseline else {
+            return Ok(());
+        };
+
+        for change in accepted {
+            match &change.state {
+                Some(state) => {
+                    baseline.files.insert(change.path.clone(), state.clone());
+                }
+                None => {
+                    baseline.files.remove(&change.path);
+                }
+            }
+        }
+
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        write_atomic(&self.path, &serde_json::to_vec(&self.baseline)?)
+    }
+}
+
+/// Files that were added, removed or modified since `baseline`, by path
+fn diff(
+    baseline: &BTreeMap<String, FileState>,
+    current: &BTreeMap<String, FileState>,
+) -> Vec<FileChange> {
+    let mut changes = Vec::new();
+
+    for (path, before) in baseline {
+        match current.get(path) {
+            Some(after) if after == before => {}
+            Some(after) => changes.push(FileChange {
+                path: path.clone(),
+                kind: FileChangeKind::Modified,
+                baseline: Some(before.clone()),
+                current: Some(after.clone()),
+            }),
+            None => changes.push(FileChange {
+                path: path.clone(),
+                kind: FileChangeKind::Removed,
+                baseline: Some(before.clone()),
+                current: None,
+            }),
+        }
+    }
+
+    for (path, after) in current {
+        if !baseline.contains_key(path) {
+            changes.push(FileChange {
+                path: path.clone(),
+                kind: FileChangeKind::Added,
+                baseline: None,
+                current: Some(after.clone()),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+/// Record the state of every file under `roots`, without following symlinks.
+///
+/// Fails if there are more than `MAX_MONITORED_FILES` files, since a partial
+/// scan would report the files it missed as removed.
+fn scan(roots: &[String]) -> anyhow::Result<BTreeMap<String, FileState>> {
+    let mut files = BTreeMap::new();
+    let mut pending: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+
+    while let Some(path) = pending.pop() {
+        let metadata = match fs::symlink_metadata(&path) {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::debug!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        if metadata.is_dir() {
+            match fs::read_dir(&path) {
+                Ok(entries) => pending.extend(entries.flatten().map(|entry| entry.path())),
+                Err(e) => tracing::warn!("Failed to list {}: {}", path.display(), e),
+            }
+            continue;
+        }
+        if !metadata.is_file() && !metadata.is_symlink() {
+            continue;
+        }
+
+        if files.len() == MAX_MONITORED_FILES {
+            anyhow::bail!(
+                "Monitored directories hold more than {} files; narrow FIM_PATHS",
+                MAX_MONITORED_FILES
+            );
+        }
+        files.insert(path.display().to_string(), file_state(&path, &metadata));
+    }
+
+    Ok(files)
+}
+
+fn file_state(path: &Path, metadata: &fs::Metadata) -> FileState {
+    let sha256 = if metadata.is_symlink() {
+        fs::read_link(path).map(|target| {
+            format!(
+                "{:x}",
+                Sha256::digest(target.as_os_str().as_encoded_bytes())
+            )
+        })
+    } else {
+        hash_file(path)
+    };
+    let sha256 = sha256
+        .map_err(|e| tracing::debug!("Failed to hash {}: {}", path.display(), e))
+        .ok();
+
+    #[cfg(unix)]
+    let (mode, uid, gid) = {
+        use std::os::unix::fs::MetadataExt;
+        (
+            Some(format!("{:04o}", metadata.mode() & 0o7777)),
+            Some(metadata.uid()),
+            Some(metadata.gid()),
+        )
+    };
+    #[cfg(not(unix))]
+    let (mode, uid, gid) = (None, None, None);
+
+    FileState {
+        sha256,
+        size: metadata.len(),
+        mode,
+        uid,
+        gid,
+    }
+}
+
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -2,20 +2,27 @@ mod checks;
 mod client;
 mod collectors;
 mod config;
+mod fim;
+mod scheduler;
 #[cfg(windows)]
 mod service;
+mod state;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use chrono::Utc;
-use common::{AgentCheckResult, RegisterRequest};
+use common::signing;
+use common::RegisterRequest;
 use tokio::time::interval;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use uuid::Uuid;
 
 use crate::checks::CheckExecutor;
 use crate::client::ServerClient;
 use crate::collectors::SystemCollector;
 use crate::config::Config;
+use crate::fim::FileIntegrityMonitor;
+use crate::scheduler::Scheduler;
 
 const AGENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -39,6 +46,13 @@ fn print_usage() {
     eprintln!("    AGENT_SECRET             Agent secret (required if not passed as argument)");
     eprintln!("    COLLECTION_INTERVAL_SECS Collection interval in seconds (default: 300)");
     eprintln!("    HOSTNAME_OVERRIDE        Override detected hostname");
+    eprintln!("    MAX_CONCURRENT_CHECKS    Checks executed at the same time (default: 4)");
+    eprintln!("    STATE_DIR                Directory for agent state (default: /var/lib/endpoint-agent)");
+    eprintln!("    FIM_PATHS                Comma-separated directories monitored for file changes");
+    eprintln!("    FIM_INTERVAL_SECS        File integrity scan interval in seconds (default: 3600)");
+    eprintln!("    ALLOW_ROOT_COMMANDS      Allow command checks to run as root (default: false)");
+    eprintln!("    COMMAND_ALLOWLIST_FILE   File listing the commands command checks may run");
+    eprintln!("    CHECK_SIGNING_PUBLIC_KEY Server public key check bundles must be signed with");
     eprintln!("    RUST_LOG                 Log level (default: info)");
 }
 
@@ -100,11 +114,32 @@ pub async fn run_agent() -> anyhow::Result<()> {
 
     tracing::info!("Starting Endpoint Assessment Agent v{}", AGENT_VERSION);
     tracing::info!("Server URL: {}", config.server_url);
+    if config.allow_root_commands {
+        tracing::warn!("ALLOW_ROOT_COMMANDS is set: command checks may run as root");
+    }
 
     // Initialize components
     let mut collector = SystemCollector::new();
-    let mut executor = CheckExecutor::new();
-    let client = ServerClient::new(&config.server_url, &config.agent_secret);
+    let executor = CheckExecutor::new(&config)?;
+    let mut scheduler = Scheduler::load(&config.state_dir);
+    let fim_paths = config.fim_paths();
+    let mut fim = if fim_paths.is_empty() {
+        None
+    } else {
+        tracing::info!("Monitoring files under: {}", fim_paths.join(", "));
+        Some(FileIntegrityMonitor::load(&config.state_dir, fim_paths))
+    };
+    let mut last_fim_scan: Option<Instant> = None;
+    let mut client = ServerClient::new(&config.server_url, &config.agent_secret);
+    match &config.check_signing_public_key {
+        Some(encoded) => {
+            let key = signing::parse_verifying_key(encoded).map_err(anyhow::Error::msg)?;
+            client = client.with_verifying_key(key, &config.state_dir);
+        }
+        None => tracing::warn!(
+            "CHECK_SIGNING_PUBLIC_KEY is not set: check bundles from the server are not verified"
+        ),
+    }
 
     // Register with server
     let hostname = config
@@ -120,6 +155,11 @@ pub async fn run_agent() -> anyhow::Result<()> {
         os_version: collector.get_os_version(),
         agent_version: AGENT_VERSION.to_string(),
         ip_addresses: collector.get_ip_addresses(),
+        supported_check_types: executor
+            .supported_check_types()
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
     };
 
     let endpoint_id = loop {
@@ -157,42 +197,39 @@ pub async fn run_agent() -> anyhow::Result<()> {
             Err(e) => tracing::error!("Failed to send heartbeat: {}", e),
         }
 
+        // Scan monitored files when due
+        if let Some(fim) = &mut fim {
+            let interval = Duration::from_secs(config.fim_interval_secs);
+            if last_fim_scan.is_none_or(|t| t.elapsed() >= interval) {
+                last_fim_scan = Some(Instant::now());
+                if let Err(e) = fim.scan_and_report(&client, endpoint_id).await {
+                    tracing::error!("File integrity scan failed: {:#}", e);
+                }
+            }
+        }
+
         // Fetch and execute checks
         match client.get_checks().await {
             Ok(checks_response) => {
-                if checks_response.checks.is_empty() {
-                    tracing::debug!("No checks to execute");
+                let now = Utc::now();
+                let check_ids: Vec<Uuid> = checks_response.checks.iter().map(|c| c.id).collect();
+                let due = scheduler.due(checks_response.checks, now);
+
+                if due.is_empty() {
+                    scheduler.record(&check_ids, &[], now);
+                    tracing::debug!("No checks due");
                     continue;
                 }
 
-                tracing::info!("Executing {} checks", checks_response.checks.len());
-
-                let mut results: Vec<AgentCheckResult> = Vec::new();
-
-                for check in &checks_response.checks {
-                    tracing::debug!("Executing check: {} ({})", check.name, check.check_type);
+                tracing::info!("Executing {} of {} checks", due.len(), check_ids.len());
 
-                    let result = executor.execute(check);
-
-                    tracing::info!(
-                        "Check '{}': {:?} - {}",
-                        check.name,
-                        result.status,
-                        result.message.as_deref().unwrap_or("")
-                    );
-
-                    results.push(AgentCheckResult {
-                        check_id: check.id,
-                        status: result.status,
-                        message: result.message,
-                        collected_at: Utc::now(),
-                    });
-                }
+                let results = executor.execute_all(due).await;
 
-                // Submit results
-                match client.submit_results(endpoint_id, results).await {
+                // Submit results; checks only count as run once the server has them
+                match client.submit_results(endpoint_id, results.clone()).await {
                     Ok(response) => {
                         tracing::info!("Submitted {} check results", response.accepted);
+                        scheduler.record(&check_ids, &results, now);
                     }
                     Err(e) => {
                         tracing::error!("Failed to submit check results: {}", e);
@@ -0,0 +1,92 @@
+//! Decides which checks are due in a collection cycle.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use common::{AgentCheckDefinition, AgentCheckResult, CheckStatus};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::state::write_atomic;
+
+/// File in the state directory holding the last run time of each check
+const STATE_FILE: &str = "schedule.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScheduleState {
+    last_runs: HashMap<Uuid, DateTime<Utc>>,
+}
+
+/// Tracks when each check last ran, persisted across agent restarts so
+/// checks scheduled daily do not run again every time the agent starts.
+pub struct Scheduler {
+    path: PathBuf,
+    state: ScheduleState,
+}
+
+impl Scheduler {
+    /// Load the last run times from `state_dir`.
+    ///
+    /// A missing or unreadable state file starts with no history, which runs
+    /// every check once.
+    pub fn load(state_dir: &str) -> Self {
+        let path = Path::new(state_dir).join(STATE_FILE);
+
+        let state = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid schedule state {}: {}", path.display(), e);
+                ScheduleState::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ScheduleState::default(),
+            Err(e) => {
+                tracing::warn!("Failed to read schedule state {}: {}", path.display(), e);
+                ScheduleState::default()
+            }
+        };
+
+        Self { path, state }
+    }
+
+    /// Checks from the bundle that are due at `now`
+    pub fn due(
+        &self,
+        checks: Vec<AgentCheckDefinition>,
+        now: DateTime<Utc>,
+    ) -> Vec<AgentCheckDefinition> {
+        checks
+            .into_iter()
+            .filter(|check| match &check.schedule {
+                Some(schedule) => {
+                    schedule.is_due(self.state.last_runs.get(&check.id).copied(), now)
+                }
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Record the checks whose results the server accepted and save the state.
+    ///
+    /// Checks that reported an error stay due, so they are retried on the next
+    /// cycle. Checks no longer in `current` are forgotten, so the file does not
+    /// grow as definitions are deleted.
+    pub fn record(&mut self, current: &[Uuid], results: &[AgentCheckResult], now: DateTime<Utc>) {
+        let known = self.state.last_runs.len();
+        self.state.last_runs.retain(|id, _| current.contains(id));
+        if results.is_empty() && self.state.last_runs.len() == known {
+            return;
+        }
+
+        for result in results.iter().filter(|r| r.status != CheckStatus::Error) {
+            self.state.last_runs.insert(result.check_id, now);
+        }
+
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to save schedule state: {:#}", e);
+        }
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        write_atomic(&self.path, &serde_json::to_vec(&self.state)?)
+    }
+}
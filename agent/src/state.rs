@@ -0,0 +1,24 @@
+//! Files the agent keeps in its state directory.
+
+use std::path::Path;
+
+use anyhow::Context;
+
+/// Replace the file at `path` with `contents`, creating its directory.
+///
+/// The contents go to a temporary file that is then renamed over `path`, so a
+/// crash never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = Path::new(&tmp);
+    std::fs::write(tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+
+    Ok(())
+}
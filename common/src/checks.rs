@@ -1,13 +1,31 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::protocol::FieldError;
 
 /// Check type identifier (for database storage)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CheckTypeId {
     FileExists,
     FileContent,
+    FilePermissions,
+    FileHash,
     RegistryKey,
     ConfigSetting,
+    StructuredConfig,
+    SshdSetting,
+    ServiceState,
+    PackageInstalled,
+    Sysctl,
+    KernelModule,
+    LocalAccount,
+    MountOptions,
+    ResourceThreshold,
     ProcessRunning,
     PortOpen,
     CommandOutput,
@@ -18,8 +36,19 @@ impl std::fmt::Display for CheckTypeId {
         let s = match self {
             CheckTypeId::FileExists => "file_exists",
             CheckTypeId::FileContent => "file_content",
+            CheckTypeId::FilePermissions => "file_permissions",
+            CheckTypeId::FileHash => "file_hash",
             CheckTypeId::RegistryKey => "registry_key",
             CheckTypeId::ConfigSetting => "config_setting",
+            CheckTypeId::StructuredConfig => "structured_config",
+            CheckTypeId::SshdSetting => "sshd_setting",
+            CheckTypeId::ServiceState => "service_state",
+            CheckTypeId::PackageInstalled => "package_installed",
+            CheckTypeId::Sysctl => "sysctl",
+            CheckTypeId::KernelModule => "kernel_module",
+            CheckTypeId::LocalAccount => "local_account",
+            CheckTypeId::MountOptions => "mount_options",
+            CheckTypeId::ResourceThreshold => "resource_threshold",
             CheckTypeId::ProcessRunning => "process_running",
             CheckTypeId::PortOpen => "port_open",
             CheckTypeId::CommandOutput => "command_output",
@@ -35,8 +64,19 @@ impl std::str::FromStr for CheckTypeId {
         match s {
             "file_exists" => Ok(CheckTypeId::FileExists),
             "file_content" => Ok(CheckTypeId::FileContent),
+            "file_permissions" => Ok(CheckTypeId::FilePermissions),
+            "file_hash" => Ok(CheckTypeId::FileHash),
             "registry_key" => Ok(CheckTypeId::RegistryKey),
             "config_setting" => Ok(CheckTypeId::ConfigSetting),
+            "structured_config" => Ok(CheckTypeId::StructuredConfig),
+            "sshd_setting" => Ok(CheckTypeId::SshdSetting),
+            "service_state" => Ok(CheckTypeId::ServiceState),
+            "package_installed" => Ok(CheckTypeId::PackageInstalled),
+            "sysctl" => Ok(CheckTypeId::Sysctl),
+            "kernel_module" => Ok(CheckTypeId::KernelModule),
+            "local_account" => Ok(CheckTypeId::LocalAccount),
+            "mount_options" => Ok(CheckTypeId::MountOptions),
+            "resource_threshold" => Ok(CheckTypeId::ResourceThreshold),
             "process_running" => Ok(CheckTypeId::ProcessRunning),
             "port_open" => Ok(CheckTypeId::PortOpen),
             "command_output" => Ok(CheckTypeId::CommandOutput),
@@ -45,16 +85,138 @@ impl std::str::FromStr for CheckTypeId {
     }
 }
 
+/// A check type together with its parameters.
+///
+/// This is the single typed model for checks: the server validates and
+/// normalizes definitions through it, and the agent executes from it. It
+/// serializes as `{"check_type": "...", "parameters": {...}}`, matching the
+/// columns in `check_definitions` and the fields of `AgentCheckDefinition`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "check_type", content = "parameters", rename_all = "snake_case")]
+pub enum CheckType {
+    FileExists(FileExistsParams),
+    FileContent(FileContentParams),
+    FilePermissions(FilePermissionsParams),
+    FileHash(FileHashParams),
+    RegistryKey(RegistryKeyParams),
+    ConfigSetting(ConfigSettingParams),
+    StructuredConfig(StructuredConfigParams),
+    SshdSetting(SshdSettingParams),
+    ServiceState(ServiceStateParams),
+    PackageInstalled(PackageInstalledParams),
+    Sysctl(SysctlParams),
+    KernelModule(KernelModuleParams),
+    LocalAccount(LocalAccountParams),
+    MountOptions(MountOptionsParams),
+    ResourceThreshold(ResourceThresholdParams),
+    ProcessRunning(ProcessRunningParams),
+    PortOpen(PortOpenParams),
+    CommandOutput(CommandOutputParams),
+}
+
+impl CheckType {
+    /// Parse and validate a check type and its parameters.
+    ///
+    /// Errors are reported per field, with parameter fields prefixed by `parameters.`.
+    pub fn from_parts(
+        check_type: &str,
+        parameters: &serde_json::Value,
+    ) -> Result<Self, Vec<FieldError>> {
+        let type_id: CheckTypeId = check_type
+            .parse()
+            .map_err(|e: String| vec![FieldError::new("check_type", e)])?;
+
+        Ok(match type_id {
+            CheckTypeId::FileExists => CheckType::FileExists(parse_params(parameters)?),
+            CheckTypeId::FileContent => CheckType::FileContent(parse_params(parameters)?),
+            CheckTypeId::FilePermissions => CheckType::FilePermissions(parse_params(parameters)?),
+            CheckTypeId::FileHash => CheckType::FileHash(parse_params(parameters)?),
+            CheckTypeId::RegistryKey => CheckType::RegistryKey(parse_params(parameters)?),
+            CheckTypeId::ConfigSetting => CheckType::ConfigSetting(parse_params(parameters)?),
+            CheckTypeId::StructuredConfig => CheckType::StructuredConfig(parse_params(parameters)?),
+            CheckTypeId::SshdSetting => CheckType::SshdSetting(parse_params(parameters)?),
+            CheckTypeId::ServiceState => CheckType::ServiceState(parse_params(parameters)?),
+            CheckTypeId::PackageInstalled => CheckType::PackageInstalled(parse_params(parameters)?),
+            CheckTypeId::Sysctl => CheckType::Sysctl(parse_params(parameters)?),
+            CheckTypeId::KernelModule => CheckType::KernelModule(parse_params(parameters)?),
+            CheckTypeId::LocalAccount => CheckType::LocalAccount(parse_params(parameters)?),
+            CheckTypeId::MountOptions => CheckType::MountOptions(parse_params(parameters)?),
+            CheckTypeId::ResourceThreshold => {
+                CheckType::ResourceThreshold(parse_params(parameters)?)
+            }
+            CheckTypeId::ProcessRunning => CheckType::ProcessRunning(parse_params(parameters)?),
+            CheckTypeId::PortOpen => CheckType::PortOpen(parse_params(parameters)?),
+            CheckTypeId::CommandOutput => CheckType::CommandOutput(parse_params(parameters)?),
+        })
+    }
+
+    pub fn type_id(&self) -> CheckTypeId {
+        match self {
+            CheckType::FileExists(_) => CheckTypeId::FileExists,
+            CheckType::FileContent(_) => CheckTypeId::FileContent,
+            CheckType::FilePermissions(_) => CheckTypeId::FilePermissions,
+            CheckType::FileHash(_) => CheckTypeId::FileHash,
+            CheckType::RegistryKey(_) => CheckTypeId::RegistryKey,
+            CheckType::ConfigSetting(_) => CheckTypeId::ConfigSetting,
+            CheckType::StructuredConfig(_) => CheckTypeId::StructuredConfig,
+            CheckType::SshdSetting(_) => CheckTypeId::SshdSetting,
+            CheckType::ServiceState(_) => CheckTypeId::ServiceState,
+            CheckType::PackageInstalled(_) => CheckTypeId::PackageInstalled,
+            CheckType::Sysctl(_) => CheckTypeId::Sysctl,
+            CheckType::KernelModule(_) => CheckTypeId::KernelModule,
+            CheckType::LocalAccount(_) => CheckTypeId::LocalAccount,
+            CheckType::MountOptions(_) => CheckTypeId::MountOptions,
+            CheckType::ResourceThreshold(_) => CheckTypeId::ResourceThreshold,
+            CheckType::ProcessRunning(_) => CheckTypeId::ProcessRunning,
+            CheckType::PortOpen(_) => CheckTypeId::PortOpen,
+            CheckType::CommandOutput(_) => CheckTypeId::CommandOutput,
+        }
+    }
+
+    /// Parameters as JSON, with defaults filled in
+    pub fn parameters(&self) -> serde_json::Value {
+        let value = match self {
+            CheckType::FileExists(p) => serde_json::to_value(p),
+            CheckType::FileContent(p) => serde_json::to_value(p),
+            CheckType::FilePermissions(p) => serde_json::to_value(p),
+            CheckType::FileHash(p) => serde_json::to_value(p),
+            CheckType::RegistryKey(p) => serde_json::to_value(p),
+            CheckType::ConfigSetting(p) => serde_json::to_value(p),
+            CheckType::StructuredConfig(p) => serde_json::to_value(p),
+            CheckType::SshdSetting(p) => serde_json::to_value(p),
+            CheckType::ServiceState(p) => serde_json::to_value(p),
+            CheckType::PackageInstalled(p) => serde_json::to_value(p),
+            CheckType::Sysctl(p) => serde_json::to_value(p),
+            CheckType::KernelModule(p) => serde_json::to_value(p),
+            CheckType::LocalAccount(p) => serde_json::to_value(p),
+            CheckType::MountOptions(p) => serde_json::to_value(p),
+            CheckType::ResourceThreshold(p) => serde_json::to_value(p),
+            CheckType::ProcessRunning(p) => serde_json::to_value(p),
+            CheckType::PortOpen(p) => serde_json::to_value(p),
+            CheckType::CommandOutput(p) => serde_json::to_value(p),
+        };
+        value.unwrap_or_default()
+    }
+}
+
+/// JSON Schema describing every check type and its parameters
+pub fn check_schema() -> schemars::schema::RootSchema {
+    schemars::schema_for!(CheckType)
+}
+
 /// Parameters for file_exists check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct FileExistsParams {
+    #[validate(length(min = 1))]
     pub path: String,
 }
 
 /// Parameters for file_content check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct FileContentParams {
+    #[validate(length(min = 1))]
     pub path: String,
+    #[validate(custom = "validate_regex")]
     pub pattern: String,
     #[serde(default = "default_true")]
     pub should_match: bool,
@@ -64,39 +226,1088 @@ fn default_true() -> bool {
     true
 }
 
+/// Parameters for file_permissions check (Unix only)
+///
+/// Modes are octal strings such as `"0640"`. At least one expectation must be set.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_file_permissions"))]
+pub struct FilePermissionsParams {
+    #[validate(length(min = 1))]
+    pub path: String,
+    /// Exact permission bits the file must have
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_octal_mode")]
+    pub mode: Option<String>,
+    /// Most permissive mode allowed; any bit outside it fails the check,
+    /// so `"0644"` rejects group- and world-writable files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_octal_mode")]
+    pub max_mode: Option<String>,
+    /// Owning user, by name or uid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub owner: Option<String>,
+    /// Owning group, by name or gid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub group: Option<String>,
+    /// Expected file type; `symlink` inspects the link itself rather than its target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_type: Option<FileKind>,
+}
+
+/// Kind of filesystem entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    File,
+    Directory,
+    Symlink,
+}
+
+impl std::fmt::Display for FileKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileKind::File => write!(f, "file"),
+            FileKind::Directory => write!(f, "directory"),
+            FileKind::Symlink => write!(f, "symlink"),
+        }
+    }
+}
+
+/// Parse permission bits written in octal, such as `"0640"` or `"755"`
+pub fn parse_octal_mode(mode: &str) -> Result<u32, String> {
+    match u32::from_str_radix(mode.trim(), 8) {
+        Ok(bits) if bits <= 0o7777 => Ok(bits),
+        _ => Err(format!(
+            "invalid mode {:?}: expected octal between 0000 and 7777",
+            mode
+        )),
+    }
+}
+
+fn validate_octal_mode(mode: &str) -> Result<(), ValidationError> {
+    parse_octal_mode(mode).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("mode");
+        error.message = Some(e.into());
+        error
+    })
+}
+
+fn validate_file_permissions(params: &FilePermissionsParams) -> Result<(), ValidationError> {
+    if params.mode.is_none()
+        && params.max_mode.is_none()
+        && params.owner.is_none()
+        && params.group.is_none()
+        && params.file_type.is_none()
+    {
+        let mut error = ValidationError::new("expectation");
+        error.message =
+            Some("set at least one of mode, max_mode, owner, group and file_type".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
 /// Parameters for registry_key check (Windows only)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_registry_key"))]
 pub struct RegistryKeyParams {
+    #[validate(length(min = 1))]
     pub path: String,
     pub value_name: Option<String>,
-    pub expected: Option<String>,
+    /// Operand of `operator`; without it the check only requires the value to exist
+    pub expected: Option<serde_json::Value>,
+    #[serde(default)]
+    pub operator: ComparisonOperator,
+    #[serde(default)]
+    pub if_missing: MissingKey,
 }
 
 /// Parameters for config_setting check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_config_setting"))]
 pub struct ConfigSettingParams {
+    #[validate(length(min = 1))]
+    pub file: String,
+    #[validate(length(min = 1))]
+    pub key: String,
+    /// Operand of `operator`, omitted for `absent`
+    #[serde(default)]
+    pub expected: Option<serde_json::Value>,
+    #[serde(default)]
+    pub operator: ComparisonOperator,
+    #[serde(default)]
+    pub if_missing: MissingKey,
+}
+
+/// Parameters for structured_config check
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_structured_config"))]
+pub struct StructuredConfigParams {
+    #[validate(length(min = 1))]
     pub file: String,
+    /// Inferred from the file extension when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<ConfigFormat>,
+    /// Path to the value, such as `server.tls.min_version` or `servers[0].port`;
+    /// `section.key` in INI files
+    #[validate(custom = "validate_key_path")]
+    pub key: String,
+    pub operator: ComparisonOperator,
+    /// Operand of the operator: a list for `in`, a pattern for `regex`, a
+    /// number for `ge` and `le`, omitted for `absent`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub if_missing: MissingKey,
+}
+
+/// Format of a structured configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+    Ini,
+}
+
+impl ConfigFormat {
+    /// Format implied by a file extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(ConfigFormat::Json),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "toml" => Some(ConfigFormat::Toml),
+            "ini" => Some(ConfigFormat::Ini),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigFormat::Json => write!(f, "json"),
+            ConfigFormat::Yaml => write!(f, "yaml"),
+            ConfigFormat::Toml => write!(f, "toml"),
+            ConfigFormat::Ini => write!(f, "ini"),
+        }
+    }
+}
+
+/// How an observed configuration value is compared with the expected one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOperator {
+    /// Same value, compared as text so `1.2` equals `"1.2"`
+    #[default]
+    #[serde(alias = "eq")]
+    Equals,
+    #[serde(alias = "ne")]
+    NotEquals,
+    /// Numerically less than
+    Lt,
+    /// Numerically less than or equal
+    Le,
+    /// Numerically greater than
+    Gt,
+    /// Numerically greater than or equal
+    Ge,
+    /// Numerically within an inclusive `[min, max]` range
+    Between,
+    /// Equals one of a list of values
+    In,
+    /// Equals none of a list of values
+    NotIn,
+    /// Matches a regular expression
+    Regex,
+    /// Same version, so `1.2` equals `1.2.0`
+    VersionEq,
+    VersionLt,
+    VersionLe,
+    VersionGt,
+    VersionGe,
+    /// The key is not set
+    Absent,
+}
+
+impl ComparisonOperator {
+    fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            ComparisonOperator::Lt
+                | ComparisonOperator::Le
+                | ComparisonOperator::Gt
+                | ComparisonOperator::Ge
+        )
+    }
+
+    fn is_version(self) -> bool {
+        matches!(
+            self,
+            ComparisonOperator::VersionEq
+                | ComparisonOperator::VersionLt
+                | ComparisonOperator::VersionLe
+                | ComparisonOperator::VersionGt
+                | ComparisonOperator::VersionGe
+        )
+    }
+}
+
+impl std::fmt::Display for ComparisonOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComparisonOperator::Equals => write!(f, "equals"),
+            ComparisonOperator::NotEquals => write!(f, "not_equals"),
+            ComparisonOperator::Lt => write!(f, "lt"),
+            ComparisonOperator::Le => write!(f, "le"),
+            ComparisonOperator::Gt => write!(f, "gt"),
+            ComparisonOperator::Ge => write!(f, "ge"),
+            ComparisonOperator::Between => write!(f, "between"),
+            ComparisonOperator::In => write!(f, "in"),
+            ComparisonOperator::NotIn => write!(f, "not_in"),
+            ComparisonOperator::Regex => write!(f, "regex"),
+            ComparisonOperator::VersionEq => write!(f, "version_eq"),
+            ComparisonOperator::VersionLt => write!(f, "version_lt"),
+            ComparisonOperator::VersionLe => write!(f, "version_le"),
+            ComparisonOperator::VersionGt => write!(f, "version_gt"),
+            ComparisonOperator::VersionGe => write!(f, "version_ge"),
+            ComparisonOperator::Absent => write!(f, "absent"),
+        }
+    }
+}
+
+/// Result of a comparison when the key is not set. `absent` always passes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingKey {
+    #[default]
+    Fail,
+    Pass,
+}
+
+/// Split a key path such as `servers[0].port` into `["servers", "0", "port"]`
+pub fn key_path_segments(key: &str) -> Result<Vec<String>, String> {
+    let mut segments = Vec::new();
+
+    for part in key.split('.') {
+        let (name, mut indices) = match part.find('[') {
+            Some(i) => (&part[..i], &part[i..]),
+            None => (part, ""),
+        };
+        if name.is_empty() && (segments.is_empty() || indices.is_empty()) {
+            return Err(format!("invalid key path {:?}: empty segment", key));
+        }
+        if !name.is_empty() {
+            segments.push(name.to_string());
+        }
+
+        while !indices.is_empty() {
+            let index = indices
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once(']'))
+                .filter(|(index, _)| index.parse::<usize>().is_ok());
+            let Some((index, rest)) = index else {
+                return Err(format!(
+                    "invalid key path {:?}: bad index in {:?}",
+                    key, part
+                ));
+            };
+            segments.push(index.to_string());
+            indices = rest;
+        }
+    }
+
+    Ok(segments)
+}
+
+fn validate_key_path(key: &str) -> Result<(), ValidationError> {
+    key_path_segments(key).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("key");
+        error.message = Some(e.into());
+        error
+    })
+}
+
+/// Check that `value` is a usable operand for `operator`
+fn validate_operand(
+    operator: ComparisonOperator,
+    value: Option<&serde_json::Value>,
+) -> Result<(), ValidationError> {
+    use serde_json::Value;
+
+    let is_scalar = |v: &Value| matches!(v, Value::String(_) | Value::Number(_) | Value::Bool(_));
+    let is_number = |v: &Value| match v {
+        Value::Number(_) => true,
+        Value::String(s) => s.trim().parse::<f64>().is_ok(),
+        _ => false,
+    };
+    let problem = match (operator, value) {
+        (ComparisonOperator::Absent, None) => None,
+        (ComparisonOperator::Absent, Some(_)) => Some("absent takes no value".to_string()),
+        (_, None) => Some(format!("{} requires a value", operator)),
+        (ComparisonOperator::Equals | ComparisonOperator::NotEquals, Some(v)) if !is_scalar(v) => {
+            Some(format!("{} requires a string, number or boolean", operator))
+        }
+        (ComparisonOperator::In | ComparisonOperator::NotIn, Some(Value::Array(items)))
+            if !items.is_empty() && items.iter().all(is_scalar) =>
+        {
+            None
+        }
+        (ComparisonOperator::In | ComparisonOperator::NotIn, Some(_)) => {
+            Some(format!("{} requires a non-empty list of values", operator))
+        }
+        (ComparisonOperator::Regex, Some(Value::String(pattern))) => regex::Regex::new(pattern)
+            .err()
+            .map(|e| format!("invalid regex: {}", e)),
+        (ComparisonOperator::Regex, Some(_)) => Some("regex requires a pattern string".into()),
+        (op, Some(v)) if op.is_numeric() => {
+            (!is_number(v)).then(|| format!("{} requires a number", operator))
+        }
+        (ComparisonOperator::Between, Some(Value::Array(bounds)))
+            if bounds.len() == 2 && bounds.iter().all(is_number) =>
+        {
+            None
+        }
+        (ComparisonOperator::Between, Some(_)) => {
+            Some("between requires a [min, max] list of numbers".into())
+        }
+        (op, Some(v)) if op.is_version() => match v {
+            Value::String(s) if !s.trim().is_empty() => None,
+            Value::Number(_) => None,
+            _ => Some(format!("{} requires a version string", operator)),
+        },
+        _ => None,
+    };
+
+    match problem {
+        Some(message) => {
+            let mut error = ValidationError::new("value");
+            error.message = Some(message.into());
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+fn validate_structured_config(params: &StructuredConfigParams) -> Result<(), ValidationError> {
+    if params.format.is_none() && ConfigFormat::from_path(&params.file).is_none() {
+        let mut error = ValidationError::new("format");
+        error.message = Some(
+            "format is required when the file extension is not .json, .yaml, .yml, .toml or .ini"
+                .into(),
+        );
+        return Err(error);
+    }
+    validate_operand(params.operator, params.value.as_ref())
+}
+
+fn validate_config_setting(params: &ConfigSettingParams) -> Result<(), ValidationError> {
+    validate_operand(params.operator, params.expected.as_ref())
+}
+
+fn validate_registry_key(params: &RegistryKeyParams) -> Result<(), ValidationError> {
+    if params.value_name.is_none() {
+        if params.expected.is_some() {
+            let mut error = ValidationError::new("value_name");
+            error.message = Some("value_name is required to compare a value".into());
+            return Err(error);
+        }
+        return Ok(());
+    }
+    // Without an operand the check only requires the value to exist
+    if params.expected.is_none() && params.operator == ComparisonOperator::Equals {
+        return Ok(());
+    }
+    validate_operand(params.operator, params.expected.as_ref())
+}
+
+/// Main configuration file of the OpenSSH server
+pub const DEFAULT_SSHD_CONFIG: &str = "/etc/ssh/sshd_config";
+
+/// Parameters for sshd_setting check
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_sshd_setting"))]
+pub struct SshdSettingParams {
+    /// sshd_config keyword such as `PermitRootLogin`; case does not matter
+    #[validate(custom = "validate_sshd_keyword")]
+    pub key: String,
+    #[serde(default)]
+    pub operator: ComparisonOperator,
+    /// Operand of `operator`, omitted for `absent`
+    #[serde(default)]
+    pub expected: Option<serde_json::Value>,
+    #[serde(default)]
+    pub if_missing: MissingKey,
+    /// Configuration file to evaluate
+    #[serde(default = "default_sshd_config")]
+    #[validate(length(min = 1))]
+    pub config_file: String,
+}
+
+fn default_sshd_config() -> String {
+    DEFAULT_SSHD_CONFIG.to_string()
+}
+
+fn validate_sshd_keyword(key: &str) -> Result<(), ValidationError> {
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric()) {
+        let mut error = ValidationError::new("key");
+        error.message = Some("key must be a single sshd_config keyword".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_sshd_setting(params: &SshdSettingParams) -> Result<(), ValidationError> {
+    validate_operand(params.operator, params.expected.as_ref())
+}
+
+/// Parameters for service_state check (Linux only)
+///
+/// Each state lists the accepted values; an empty list is not checked. At
+/// least one state must be given.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_service_state"))]
+pub struct ServiceStateParams {
+    /// systemd unit such as `auditd` or `telnet.socket`; `.service` is assumed without a suffix
+    #[validate(custom = "validate_unit_name")]
+    pub unit: String,
+    /// Accepted ActiveState values, such as `active`, `inactive` or `failed`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub active_state: Vec<String>,
+    /// Accepted SubState values, such as `running` or `exited`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_state: Vec<String>,
+    /// Accepted UnitFileState values, such as `enabled`, `disabled`, `static` or `masked`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unit_file_state: Vec<String>,
+    /// Pass when the unit is not installed at all
+    #[serde(default)]
+    pub allow_missing: bool,
+}
+
+fn validate_unit_name(unit: &str) -> Result<(), ValidationError> {
+    let valid = !unit.is_empty()
+        && !unit.starts_with('-')
+        && unit
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ":-_.@\\".contains(c));
+    if !valid {
+        let mut error = ValidationError::new("unit");
+        error.message = Some("unit must be a systemd unit name".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_service_state(params: &ServiceStateParams) -> Result<(), ValidationError> {
+    if params.active_state.is_empty()
+        && params.sub_state.is_empty()
+        && params.unit_file_state.is_empty()
+    {
+        let mut error = ValidationError::new("expectation");
+        error.message =
+            Some("set at least one of active_state, sub_state and unit_file_state".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Parameters for package_installed check (Linux only)
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_package_installed"))]
+pub struct PackageInstalledParams {
+    /// Package name as the package manager knows it, such as `openssl`
+    #[validate(custom = "validate_package_name")]
+    pub name: String,
+    /// `false` asserts that the package is not installed
+    #[serde(default = "default_true")]
+    pub installed: bool,
+    /// Version to compare the installed version with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub version: Option<String>,
+    /// One of `eq`, `ne`, `lt`, `le`, `gt` and `ge`
+    #[serde(default = "default_package_operator")]
+    pub operator: ComparisonOperator,
+    /// Detected from the host when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manager: Option<PackageManager>,
+}
+
+/// Package database queried by package_installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageManager {
+    Dpkg,
+    Rpm,
+}
+
+impl std::fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackageManager::Dpkg => write!(f, "dpkg"),
+            PackageManager::Rpm => write!(f, "rpm"),
+        }
+    }
+}
+
+fn default_package_operator() -> ComparisonOperator {
+    ComparisonOperator::Ge
+}
+
+fn validate_package_name(name: &str) -> Result<(), ValidationError> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-._:".contains(c));
+    if !valid {
+        let mut error = ValidationError::new("name");
+        error.message = Some("name must be a package name".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_package_installed(params: &PackageInstalledParams) -> Result<(), ValidationError> {
+    let problem = if params.version.is_some() && !params.installed {
+        Some("version cannot be checked for a package that must not be installed")
+    } else if !matches!(
+        params.operator,
+        ComparisonOperator::Equals
+            | ComparisonOperator::NotEquals
+            | ComparisonOperator::Lt
+            | ComparisonOperator::Le
+            | ComparisonOperator::Gt
+            | ComparisonOperator::Ge
+    ) {
+        Some("operator must be one of eq, ne, lt, le, gt and ge")
+    } else {
+        None
+    };
+
+    match problem {
+        Some(message) => {
+            let mut error = ValidationError::new("version");
+            error.message = Some(message.into());
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Parameters for sysctl check (Linux only)
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_sysctl"))]
+pub struct SysctlParams {
+    /// Kernel parameter such as `net.ipv4.ip_forward`, or in `/proc/sys` form `net/ipv4/ip_forward`
+    #[validate(custom = "validate_sysctl_key")]
     pub key: String,
-    pub expected: String,
+    #[serde(default)]
+    pub operator: ComparisonOperator,
+    /// Operand of `operator`, omitted for `absent`
+    #[serde(default)]
+    pub expected: Option<serde_json::Value>,
+    /// Applies when the parameter does not exist at runtime
+    #[serde(default)]
+    pub if_missing: MissingKey,
+    /// Also check the value the sysctl configuration files set at boot
+    #[serde(default)]
+    pub persistent: bool,
+}
+
+fn validate_sysctl_key(key: &str) -> Result<(), ValidationError> {
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-/".contains(c))
+        && key.split(['.', '/']).all(|part| !part.is_empty());
+    if !valid {
+        let mut error = ValidationError::new("key");
+        error.message = Some("key must be a kernel parameter name".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_sysctl(params: &SysctlParams) -> Result<(), ValidationError> {
+    validate_operand(params.operator, params.expected.as_ref())
+}
+
+/// Parameters for kernel_module check (Linux only)
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_kernel_module"))]
+pub struct KernelModuleParams {
+    /// Module name such as `cramfs`; `-` and `_` are interchangeable
+    #[validate(custom = "validate_module_name")]
+    pub name: String,
+    /// Require the module not to be loaded
+    #[serde(default = "default_true")]
+    pub not_loaded: bool,
+    /// Require modprobe configuration to disable the module, with an
+    /// `install` command of /bin/false or /bin/true, or a `blacklist` entry
+    #[serde(default = "default_true")]
+    pub disabled: bool,
+}
+
+fn validate_module_name(name: &str) -> Result<(), ValidationError> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        let mut error = ValidationError::new("name");
+        error.message = Some("name must be a kernel module name".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_kernel_module(params: &KernelModuleParams) -> Result<(), ValidationError> {
+    if !params.not_loaded && !params.disabled {
+        let mut error = ValidationError::new("kernel_module");
+        error.message = Some("at least one of not_loaded and disabled must be true".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Parameters for local_account check (Linux only)
+///
+/// `rule` selects what is audited; the other fields apply to some rules only.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_local_account"))]
+pub struct LocalAccountParams {
+    pub rule: AccountRule,
+    /// Account for `user_exists` and `user_absent`; for `group_members` the
+    /// account that must be a member, and for `password_aging` the only
+    /// account checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_account_name")]
+    pub user: Option<String>,
+    /// Group for `group_members`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_account_name")]
+    pub group: Option<String>,
+    /// Accounts that may match: UID 0 accounts besides root, accounts without
+    /// a password, members of the group, or accounts exempt from password aging
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+    /// Longest password lifetime accounts may have, in days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_days: Option<u32>,
+    /// Shortest time between password changes accounts must have, in days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_days: Option<u32>,
+    /// Shortest warning before a password expires accounts must have, in days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_days: Option<u32>,
+    /// Longest time accounts may stay usable after their password expires, in days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactive_days: Option<u32>,
+}
+
+/// What a local_account check audits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountRule {
+    /// `user` exists
+    UserExists,
+    /// `user` does not exist
+    UserAbsent,
+    /// No account other than root has UID 0
+    UidZero,
+    /// No account has an empty password
+    EmptyPassword,
+    /// `user` is a member of `group`, or without `user`, only `allowed`
+    /// accounts are
+    GroupMembers,
+    /// Accounts with a password meet the aging limits
+    PasswordAging,
+}
+
+impl std::fmt::Display for AccountRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AccountRule::UserExists => "user_exists",
+            AccountRule::UserAbsent => "user_absent",
+            AccountRule::UidZero => "uid_zero",
+            AccountRule::EmptyPassword => "empty_password",
+            AccountRule::GroupMembers => "group_members",
+            AccountRule::PasswordAging => "password_aging",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+fn validate_account_name(name: &str) -> Result<(), ValidationError> {
+    if name.is_empty() || name.contains(':') || name.chars().any(char::is_whitespace) {
+        let mut error = ValidationError::new("name");
+        error.message = Some("must be a user or group name".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_local_account(params: &LocalAccountParams) -> Result<(), ValidationError> {
+    for name in &params.allowed {
+        validate_account_name(name)?;
+    }
+
+    let problem = match params.rule {
+        AccountRule::UserExists | AccountRule::UserAbsent if params.user.is_none() => {
+            Some(format!("{} requires user", params.rule))
+        }
+        AccountRule::GroupMembers if params.group.is_none() => {
+            Some("group_members requires group".to_string())
+        }
+        AccountRule::PasswordAging
+            if params.max_days.is_none()
+                && params.min_days.is_none()
+                && params.warn_days.is_none()
+                && params.inactive_days.is_none() =>
+        {
+            Some(
+                "password_aging requires one of max_days, min_days, warn_days and inactive_days"
+                    .to_string(),
+            )
+        }
+        _ => None,
+    };
+
+    match problem {
+        Some(message) => {
+            let mut error = ValidationError::new("rule");
+            error.message = Some(message.into());
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Parameters for mount_options check (Linux only)
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_mount_options"))]
+pub struct MountOptionsParams {
+    /// Mount point such as `/tmp`, which must be a separate mount
+    #[validate(custom = "validate_mount_point")]
+    pub path: String,
+    /// Options that must be set, such as `nodev`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<String>,
+    /// Options that must not be set, such as `exec`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden: Vec<String>,
+}
+
+fn validate_mount_point(path: &str) -> Result<(), ValidationError> {
+    if !path.starts_with('/') {
+        let mut error = ValidationError::new("path");
+        error.message = Some("path must be an absolute mount point".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_mount_options(params: &MountOptionsParams) -> Result<(), ValidationError> {
+    let invalid = params
+        .required
+        .iter()
+        .chain(&params.forbidden)
+        .any(|option| option.is_empty() || option.contains(',') || option.contains(' '));
+    if invalid {
+        let mut error = ValidationError::new("options");
+        error.message = Some("each option must be a single mount option such as nodev".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Parameters for resource_threshold check
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_resource_threshold"))]
+pub struct ResourceThresholdParams {
+    pub resource: ResourceKind,
+    /// Highest usage that passes: a percentage, or the load average for `load`
+    pub max: f64,
+    /// Mount point for `disk` and `inodes`; every mounted filesystem when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount: Option<String>,
+    /// Load average over 1, 5 or 15 minutes
+    #[serde(default = "default_load_minutes")]
+    pub load_minutes: u32,
+    /// Divide the load average by the number of CPUs
+    #[serde(default)]
+    pub per_cpu: bool,
+}
+
+/// Resource measured by resource_threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceKind {
+    /// Percentage of disk space used
+    Disk,
+    /// Percentage of inodes used (Unix only)
+    Inodes,
+    /// Percentage of memory not available to new processes
+    Memory,
+    /// Percentage of swap used
+    Swap,
+    /// Load average (Unix only)
+    Load,
+}
+
+impl std::fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ResourceKind::Disk => "disk",
+            ResourceKind::Inodes => "inodes",
+            ResourceKind::Memory => "memory",
+            ResourceKind::Swap => "swap",
+            ResourceKind::Load => "load",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+fn default_load_minutes() -> u32 {
+    5
+}
+
+fn validate_resource_threshold(params: &ResourceThresholdParams) -> Result<(), ValidationError> {
+    let is_percent = params.resource != ResourceKind::Load;
+    let problem = if !params.max.is_finite() || params.max < 0.0 {
+        Some("max must be a non-negative number")
+    } else if is_percent && params.max > 100.0 {
+        Some("max is a percentage and cannot exceed 100")
+    } else if params.mount.is_some()
+        && !matches!(params.resource, ResourceKind::Disk | ResourceKind::Inodes)
+    {
+        Some("mount only applies to disk and inodes")
+    } else if ![1, 5, 15].contains(&params.load_minutes) {
+        Some("load_minutes must be 1, 5 or 15")
+    } else {
+        None
+    };
+
+    match problem {
+        Some(message) => {
+            let mut error = ValidationError::new("resource_threshold");
+            error.message = Some(message.into());
+            Err(error)
+        }
+        None => Ok(()),
+    }
 }
 
 /// Parameters for process_running check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct ProcessRunningParams {
+    #[validate(length(min = 1))]
     pub name: String,
 }
 
 /// Parameters for port_open check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct PortOpenParams {
+    #[validate(range(min = 1))]
     pub port: u16,
 }
 
+/// Default time a command_output check may run before it is killed
+pub const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 30;
+
+/// Parameters for file_hash check
+///
+/// Every file matched by `path` must hash to one of `expected`.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_file_hashes"))]
+pub struct FileHashParams {
+    /// File path, or a glob such as `/usr/sbin/*`
+    #[validate(length(min = 1), custom = "validate_glob")]
+    pub path: String,
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+    /// Accepted digests, as hex
+    #[validate(length(min = 1))]
+    pub expected: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    /// Length of a digest written as hex
+    pub fn hex_len(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 => 64,
+            HashAlgorithm::Sha512 => 128,
+        }
+    }
+}
+
+impl std::fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashAlgorithm::Sha256 => write!(f, "sha256"),
+            HashAlgorithm::Sha512 => write!(f, "sha512"),
+        }
+    }
+}
+
+fn validate_glob(pattern: &str) -> Result<(), ValidationError> {
+    glob::Pattern::new(pattern).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("glob");
+        error.message = Some(format!("invalid glob: {}", e).into());
+        error
+    })
+}
+
+fn validate_file_hashes(params: &FileHashParams) -> Result<(), ValidationError> {
+    let len = params.algorithm.hex_len();
+    for hash in &params.expected {
+        if hash.len() != len || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            let mut error = ValidationError::new("expected");
+            error.message = Some(
+                format!(
+                    "{:?} is not a {} digest ({} hex characters)",
+                    hash, params.algorithm, len
+                )
+                .into(),
+            );
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// Default number of bytes captured from each of stdout and stderr
+pub const DEFAULT_COMMAND_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
 /// Parameters for command_output check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Exactly one of `expected_pattern` and `expected_exit_code` must be set.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[validate(schema(function = "validate_command_expectation"))]
 pub struct CommandOutputParams {
+    #[validate(length(min = 1))]
     pub command: String,
-    pub expected_pattern: String,
+    /// Regex the combined stdout and stderr must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_regex")]
+    pub expected_pattern: Option<String>,
+    /// Exit code the command must return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_exit_code: Option<i32>,
+    /// Seconds before the command and its children are killed
+    #[serde(default = "default_command_timeout_secs")]
+    #[validate(range(min = 1, max = 3600))]
+    pub timeout_secs: u64,
+    /// Bytes captured from each of stdout and stderr; the rest is discarded
+    #[serde(default = "default_command_max_output_bytes")]
+    #[validate(range(min = 1, max = 10485760))]
+    pub max_output_bytes: usize,
+    /// Directory to run the command in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Variables to set; the agent's own environment is not inherited
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Names of agent environment variables to pass through unchanged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pass_env: Vec<String>,
+    /// User (name or uid) to run the command as (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub run_as_user: Option<String>,
+    /// Group (name or gid) to run the command as; defaults to the user's primary group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub run_as_group: Option<String>,
+}
+
+fn default_command_timeout_secs() -> u64 {
+    DEFAULT_COMMAND_TIMEOUT_SECS
+}
+
+fn default_command_max_output_bytes() -> usize {
+    DEFAULT_COMMAND_MAX_OUTPUT_BYTES
+}
+
+fn validate_command_expectation(params: &CommandOutputParams) -> Result<(), ValidationError> {
+    if params.expected_pattern.is_some() == params.expected_exit_code.is_some() {
+        let mut error = ValidationError::new("expectation");
+        error.message = Some("set exactly one of expected_pattern and expected_exit_code".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_regex(pattern: &str) -> Result<(), ValidationError> {
+    regex::Regex::new(pattern).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("regex");
+        error.message = Some(format!("invalid regex: {}", e).into());
+        error
+    })
+}
+
+/// Deserialize and validate the parameters of a single check type
+pub fn parse_params<T: DeserializeOwned + Validate>(
+    parameters: &serde_json::Value,
+) -> Result<T, Vec<FieldError>> {
+    let params: T = serde_path_to_error::deserialize(parameters).map_err(|e| {
+        let path = e.path().to_string();
+        let message = e.into_inner().to_string();
+        let field = if path != "." {
+            format!("parameters.{}", path)
+        } else if let Some(name) = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next())
+        {
+            format!("parameters.{}", name)
+        } else {
+            "parameters".to_string()
+        };
+        vec![FieldError::new(field, message)]
+    })?;
+
+    params.validate().map_err(|e| parameter_field_errors(&e))?;
+    Ok(params)
+}
+
+fn parameter_field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut fields: Vec<FieldError> = errors
+        .field_errors()
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            errors.iter().map(move |error| {
+                let message = match (&error.message, error.code.as_ref()) {
+                    (Some(message), _) => message.to_string(),
+                    (None, "length") => "must not be empty".to_string(),
+                    (None, "range") => "value is out of range".to_string(),
+                    (None, code) => format!("failed {} validation", code),
+                };
+                // Struct-level (schema) errors are reported against the whole object
+                let field = match field {
+                    "__all__" => "parameters".to_string(),
+                    field => format!("parameters.{}", field),
+                };
+                FieldError::new(field, message)
+            })
+        })
+        .collect();
+
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    fields
 }
 
 /// Helper to get check type description
@@ -104,9 +1315,22 @@ pub fn check_type_description(type_id: CheckTypeId) -> &'static str {
     match type_id {
         CheckTypeId::FileExists => "Check if a file exists at the specified path",
         CheckTypeId::FileContent => "Check if file content matches a pattern",
+        CheckTypeId::FilePermissions => "Check file mode, owner, group and type (Unix only)",
+        CheckTypeId::FileHash => "Check that files hash to an expected digest",
         CheckTypeId::RegistryKey => "Check Windows registry key value (Windows only)",
         CheckTypeId::ConfigSetting => "Check configuration file setting value",
+        CheckTypeId::StructuredConfig => "Check a value in a JSON, YAML, TOML or INI file",
+        CheckTypeId::SshdSetting => "Check the effective OpenSSH server configuration",
+        CheckTypeId::ServiceState => "Check a systemd unit's active and enabled state (Linux only)",
+        CheckTypeId::PackageInstalled => {
+            "Check that a package is installed at a version, or absent (Linux only)"
+        }
         CheckTypeId::ProcessRunning => "Check if a process is running",
+        CheckTypeId::Sysctl => "Check a kernel parameter at runtime and optionally in sysctl configuration (Linux only)",
+        CheckTypeId::KernelModule => "Check that a kernel module is not loaded and is disabled in modprobe configuration (Linux only)",
+        CheckTypeId::LocalAccount => "Audit local users and groups in /etc/passwd, /etc/shadow and /etc/group (Linux only)",
+        CheckTypeId::MountOptions => "Check that a path is a separate mount with the required options (Linux only)",
+        CheckTypeId::ResourceThreshold => "Check disk, inode, memory, swap or load usage against a threshold",
         CheckTypeId::PortOpen => "Check if a port is open/listening",
         CheckTypeId::CommandOutput => "Check command output matches a pattern",
     }
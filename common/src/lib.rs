@@ -1,7 +1,10 @@
 pub mod checks;
 pub mod models;
 pub mod protocol;
+pub mod schedule;
+pub mod signing;
 
 pub use checks::*;
 pub use models::*;
 pub use protocol::*;
+pub use schedule::*;
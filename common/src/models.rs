@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::checks::CheckType;
+use crate::schedule::CheckSchedule;
+
 /// Status of an endpoint
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -92,54 +95,6 @@ impl std::str::FromStr for Severity {
     }
 }
 
-/// Check type with parameters
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum CheckType {
-    FileExists {
-        path: String,
-    },
-    FileContent {
-        path: String,
-        pattern: String,
-        should_match: bool,
-    },
-    RegistryKey {
-        path: String,
-        value_name: Option<String>,
-        expected: Option<String>,
-    },
-    ConfigSetting {
-        file: String,
-        key: String,
-        expected: String,
-    },
-    ProcessRunning {
-        name: String,
-    },
-    PortOpen {
-        port: u16,
-    },
-    CommandOutput {
-        command: String,
-        expected_pattern: String,
-    },
-}
-
-impl CheckType {
-    pub fn type_name(&self) -> &'static str {
-        match self {
-            CheckType::FileExists { .. } => "file_exists",
-            CheckType::FileContent { .. } => "file_content",
-            CheckType::RegistryKey { .. } => "registry_key",
-            CheckType::ConfigSetting { .. } => "config_setting",
-            CheckType::ProcessRunning { .. } => "process_running",
-            CheckType::PortOpen { .. } => "port_open",
-            CheckType::CommandOutput { .. } => "command_output",
-        }
-    }
-}
-
 /// Check definition stored in database
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct CheckDefinition {
@@ -147,8 +102,12 @@ pub struct CheckDefinition {
     #[validate(length(min = 1, max = 255))]
     pub name: String,
     pub description: Option<String>,
+    #[serde(flatten)]
     pub check_type: CheckType,
     pub severity: Severity,
+    /// Runs on every collection cycle when not set
+    #[serde(default)]
+    pub schedule: Option<CheckSchedule>,
     pub enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -162,6 +121,8 @@ pub enum CheckStatus {
     Fail,
     Error,
     Skipped,
+    /// A failure covered by an active waiver (assigned by the server, never reported by agents)
+    Waived,
 }
 
 impl std::fmt::Display for CheckStatus {
@@ -171,6 +132,7 @@ impl std::fmt::Display for CheckStatus {
             CheckStatus::Fail => write!(f, "fail"),
             CheckStatus::Error => write!(f, "error"),
             CheckStatus::Skipped => write!(f, "skipped"),
+            CheckStatus::Waived => write!(f, "waived"),
         }
     }
 }
@@ -184,6 +146,7 @@ impl std::str::FromStr for CheckStatus {
             "fail" => Ok(CheckStatus::Fail),
             "error" => Ok(CheckStatus::Error),
             "skipped" => Ok(CheckStatus::Skipped),
+            "waived" => Ok(CheckStatus::Waived),
             _ => Err(format!("Unknown check status: {}", s)),
         }
     }
@@ -233,6 +196,99 @@ pub struct SystemSnapshot {
     pub installed_software: Vec<SoftwareInfo>,
 }
 
+/// State of a monitored file, as recorded in a file integrity baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileState {
+    /// SHA-256 of the contents (of the target path for symlinks), absent if unreadable
+    pub sha256: Option<String>,
+    pub size: u64,
+    /// Permission bits in octal (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+}
+
+/// How a monitored file differs from its baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl std::fmt::Display for FileChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileChangeKind::Added => write!(f, "added"),
+            FileChangeKind::Removed => write!(f, "removed"),
+            FileChangeKind::Modified => write!(f, "modified"),
+        }
+    }
+}
+
+impl std::str::FromStr for FileChangeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "added" => Ok(FileChangeKind::Added),
+            "removed" => Ok(FileChangeKind::Removed),
+            "modified" => Ok(FileChangeKind::Modified),
+            _ => Err(format!("Unknown file change: {}", s)),
+        }
+    }
+}
+
+/// Review state of a file change on the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeStatus {
+    /// Still reported by the agent and not yet reviewed
+    Open,
+    /// Accepted into the endpoint's baseline
+    Accepted,
+    /// No longer reported: the file went back to its baseline state
+    Reverted,
+}
+
+impl std::fmt::Display for FileChangeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileChangeStatus::Open => write!(f, "open"),
+            FileChangeStatus::Accepted => write!(f, "accepted"),
+            FileChangeStatus::Reverted => write!(f, "reverted"),
+        }
+    }
+}
+
+impl std::str::FromStr for FileChangeStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(FileChangeStatus::Open),
+            "accepted" => Ok(FileChangeStatus::Accepted),
+            "reverted" => Ok(FileChangeStatus::Reverted),
+            _ => Err(format!("Unknown file change status: {}", s)),
+        }
+    }
+}
+
+/// Difference between a monitored file and its baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+    /// State in the baseline, absent for added files
+    pub baseline: Option<FileState>,
+    /// State found by the scan, absent for removed files
+    pub current: Option<FileState>,
+}
+
 /// Admin user for web UI access
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdminUser {
@@ -2,7 +2,11 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::models::{CheckResult, CheckStatus, ProcessInfo, Severity, SoftwareInfo, SystemSnapshot};
+use crate::checks::CheckType;
+use crate::models::{
+    CheckStatus, FileChange, FileState, ProcessInfo, Severity, SoftwareInfo, SystemSnapshot,
+};
+use crate::schedule::CheckSchedule;
 
 /// Agent registration request
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +16,9 @@ pub struct RegisterRequest {
     pub os_version: String,
     pub agent_version: String,
     pub ip_addresses: Vec<String>,
+    /// Check types this agent has registered executors for
+    #[serde(default)]
+    pub supported_check_types: Vec<String>,
 }
 
 /// Agent registration response
@@ -67,6 +74,9 @@ pub struct HeartbeatResponse {
 }
 
 /// Check definition sent to agent
+///
+/// The type and parameters stay untyped on the wire so an agent can still
+/// load the rest of its checks when the server knows a type it does not.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentCheckDefinition {
     pub id: Uuid,
@@ -74,12 +84,47 @@ pub struct AgentCheckDefinition {
     pub check_type: String,
     pub parameters: serde_json::Value,
     pub severity: Severity,
+    /// Runs on every collection cycle when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<CheckSchedule>,
+    /// Base64 Ed25519 signature over `signing_payload()`, if the server has a signing key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl AgentCheckDefinition {
+    /// Bytes covered by `signature`
+    pub fn signing_payload(&self) -> Vec<u8> {
+        crate::signing::check_signing_payload(self.id, &self.check_type, &self.parameters)
+    }
+
+    /// Parse the check type and parameters into the typed check model
+    pub fn parse(&self) -> Result<CheckType, String> {
+        CheckType::from_parts(&self.check_type, &self.parameters).map_err(|errors| {
+            errors
+                .iter()
+                .map(|e| format!("{}: {}", e.field, e.message))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    }
 }
 
 /// Response containing check definitions for agent
+///
+/// When the server has a signing key, `signature` covers the canonical JSON
+/// of every other field, so agents can reject tampered, expired or replayed
+/// bundles (see `signing::verify_checks_response`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChecksResponse {
     pub checks: Vec<AgentCheckDefinition>,
+    /// Increases whenever check definitions change
+    pub revision: i64,
+    pub issued_at: DateTime<Utc>,
+    /// Agents must not run checks from this bundle after this time
+    pub expires_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 /// Single check result from agent
@@ -89,6 +134,15 @@ pub struct AgentCheckResult {
     pub status: CheckStatus,
     pub message: Option<String>,
     pub collected_at: DateTime<Utc>,
+    /// Time the agent spent executing the check, absent from older agents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// What the check observed, such as the actual and expected values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<serde_json::Value>,
+    /// Measured value for checks that produce a number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
 }
 
 /// Request to submit check results
@@ -105,11 +159,52 @@ pub struct SubmitResultsResponse {
     pub message: String,
 }
 
+/// File integrity report from an agent: every monitored file that differs
+/// from the agent's baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitFileChangesRequest {
+    pub endpoint_id: Uuid,
+    pub scanned_at: DateTime<Utc>,
+    pub files_scanned: u64,
+    pub changes: Vec<FileChange>,
+    /// More files changed than were reported, so unreported changes are not reverted
+    #[serde(default)]
+    pub truncated: bool,
+    /// Accepted changes applied to the baseline since the last report
+    #[serde(default)]
+    pub applied: Vec<Uuid>,
+    /// The agent took a new baseline in this scan, so changes reported before
+    /// cannot be judged against it
+    #[serde(default)]
+    pub baseline_reset: bool,
+}
+
+/// Response after submitting file changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitFileChangesResponse {
+    pub open_changes: usize,
+    /// Accepted changes to apply to the baseline, repeated until the agent
+    /// reports them as applied
+    #[serde(default)]
+    pub accepted: Vec<AcceptedFileChange>,
+}
+
+/// A file change accepted into an endpoint's baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptedFileChange {
+    pub id: Uuid,
+    pub path: String,
+    /// New baseline state of the file, absent if it was removed
+    pub state: Option<FileState>,
+}
+
 /// Error response from API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
 }
 
 impl ErrorResponse {
@@ -117,6 +212,28 @@ impl ErrorResponse {
         Self {
             error: error.into(),
             message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with_fields(mut self, fields: Vec<FieldError>) -> Self {
+        self.fields = fields;
+        self
+    }
+}
+
+/// Validation error for a single request field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
         }
     }
 }
@@ -0,0 +1,94 @@
+//! When agents run a check.
+//!
+//! Checks without a schedule run on every collection cycle. A schedule can
+//! only make a check run less often than that: agents evaluate it once per
+//! cycle and run the check if it has become due since its last run.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Shortest interval accepted for an interval schedule
+pub const MIN_SCHEDULE_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CheckSchedule {
+    /// Run when at least `seconds` have passed since the last run
+    Interval { seconds: u64 },
+    /// Run at the times given by a cron expression, evaluated in UTC.
+    ///
+    /// Five fields (minute hour day-of-month month day-of-week) as in
+    /// crontab, or six with a leading seconds field.
+    Cron { expression: String },
+}
+
+impl CheckSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            CheckSchedule::Interval { seconds } => {
+                if *seconds < MIN_SCHEDULE_INTERVAL_SECS {
+                    return Err(format!(
+                        "interval must be at least {} seconds",
+                        MIN_SCHEDULE_INTERVAL_SECS
+                    ));
+                }
+                Ok(())
+            }
+            CheckSchedule::Cron { expression } => parse_cron(expression).map(|_| ()),
+        }
+    }
+
+    /// Whether a check last run at `last_run` should run again at `now`.
+    ///
+    /// A check that has never run is always due, so it reports a result as
+    /// soon as the agent first sees it.
+    pub fn is_due(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        let Some(last_run) = last_run else {
+            return true;
+        };
+
+        match self {
+            CheckSchedule::Interval { seconds } => u64::try_from((now - last_run).num_seconds())
+                .is_ok_and(|elapsed| elapsed >= *seconds),
+            CheckSchedule::Cron { expression } => match parse_cron(expression) {
+                Ok(schedule) => schedule
+                    .after(&last_run)
+                    .next()
+                    .is_some_and(|next| next <= now),
+                // Definitions are validated on the server; an expression this
+                // agent cannot parse falls back to running every cycle
+                Err(_) => true,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for CheckSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckSchedule::Interval { seconds } => write!(f, "every {}s", seconds),
+            CheckSchedule::Cron { expression } => write!(f, "cron {}", expression),
+        }
+    }
+}
+
+fn parse_cron(expression: &str) -> Result<cron::Schedule, String> {
+    let expression = expression.trim();
+    let fields = expression.split_whitespace().count();
+
+    let normalized = match fields {
+        5 => format!("0 {}", expression),
+        6 => expression.to_string(),
+        _ => {
+            return Err(format!(
+                "cron expression must have 5 or 6 fields, got {}",
+                fields
+            ))
+        }
+    };
+
+    cron::Schedule::from_str(&normalized).map_err(|e| format!("invalid cron expression: {}", e))
+}
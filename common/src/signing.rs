@@ -0,0 +1,137 @@
+//! Ed25519 signatures over check definitions.
+//!
+//! The server signs with a private key loaded from a file, agents verify
+//! with the matching public key from their own configuration. Keys and
+//! signatures are exchanged as standard base64.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::protocol::ChecksResponse;
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+/// Parse a base64 encoded 32-byte Ed25519 private key (seed)
+pub fn parse_signing_key(encoded: &str) -> Result<SigningKey, String> {
+    let bytes: [u8; 32] = decode_fixed(encoded, "signing key")?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Parse a base64 encoded 32-byte Ed25519 public key
+pub fn parse_verifying_key(encoded: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = decode_fixed(encoded, "public key")?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("Invalid public key: {}", e))
+}
+
+/// Base64 encoding of a public key, in the form agents are configured with
+pub fn encode_verifying_key(key: &VerifyingKey) -> String {
+    STANDARD.encode(key.as_bytes())
+}
+
+pub fn sign(key: &SigningKey, payload: &[u8]) -> String {
+    STANDARD.encode(key.sign(payload).to_bytes())
+}
+
+pub fn verify(key: &VerifyingKey, payload: &[u8], signature: &str) -> Result<(), String> {
+    let bytes: [u8; 64] = decode_fixed(signature, "signature")?;
+    key.verify_strict(payload, &Signature::from_bytes(&bytes))
+        .map_err(|_| "Signature does not match".to_string())
+}
+
+/// Bytes signed for a single check definition.
+///
+/// Covers the id, type and parameters, so a signature cannot be moved to
+/// another check or survive any change to what the agent would execute.
+pub fn check_signing_payload(id: Uuid, check_type: &str, parameters: &Value) -> Vec<u8> {
+    canonical_json(&serde_json::json!({
+        "id": id,
+        "check_type": check_type,
+        "parameters": parameters,
+    }))
+}
+
+/// Sign a check bundle in place
+pub fn sign_checks_response(key: &SigningKey, response: &mut ChecksResponse) {
+    response.signature = None;
+    let value = serde_json::to_value(&*response).unwrap_or_default();
+    response.signature = Some(sign(key, &checks_response_payload(&value)));
+}
+
+/// Verify a check bundle as received on the wire and parse it.
+///
+/// The signature is checked against the raw JSON, so fields this agent does
+/// not know about are still covered. Expiry and revision ordering are left to
+/// the caller.
+pub fn verify_checks_response(key: &VerifyingKey, value: Value) -> Result<ChecksResponse, String> {
+    let signature = value
+        .get("signature")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Check bundle is not signed".to_string())?
+        .to_string();
+
+    verify(key, &checks_response_payload(&value), &signature)
+        .map_err(|e| format!("Check bundle signature rejected: {}", e))?;
+
+    serde_json::from_value(value).map_err(|e| format!("Invalid check bundle: {}", e))
+}
+
+/// Bytes signed for a check bundle: everything except the signature itself
+fn checks_response_payload(value: &Value) -> Vec<u8> {
+    let mut value = value.clone();
+    if let Value::Object(map) = &mut value {
+        map.remove("signature");
+    }
+    canonical_json(&value)
+}
+
+/// Serialize JSON with object keys sorted and no insignificant whitespace,
+/// so both sides produce identical bytes for the same value
+pub fn canonical_json(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+
+            out.push(b'{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical(&Value::String(key.clone()), out);
+                out.push(b':');
+                write_canonical(value, out);
+            }
+            out.push(b'}');
+        }
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(b']');
+        }
+        scalar => out.extend_from_slice(scalar.to_string().as_bytes()),
+    }
+}
+
+fn decode_fixed<const N: usize>(encoded: &str, what: &str) -> Result<[u8; N], String> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid {}: {}", what, e))?;
+
+    bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| format!("Invalid {}: expected {} bytes, got {}", what, N, b.len()))
+}
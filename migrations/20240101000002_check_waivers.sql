@@ -0,0 +1,30 @@
+-- Waivers for accepted check failures
+
+CREATE TABLE check_waivers (
+    id UUID PRIMARY KEY,
+    check_id UUID NOT NULL REFERENCES check_definitions(id) ON DELETE CASCADE,
+    -- NULL applies the waiver to every endpoint
+    endpoint_id UUID REFERENCES endpoints(id) ON DELETE CASCADE,
+    justification TEXT NOT NULL,
+    approved_by VARCHAR(100) NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_check_waivers_check_id ON check_waivers(check_id);
+CREATE INDEX idx_check_waivers_expires_at ON check_waivers(expires_at);
+
+-- Status of a stored result with active waivers applied. Failures covered by
+-- an unexpired waiver read as 'waived' and lapse back to 'fail' on expiry.
+CREATE FUNCTION effective_check_status(status VARCHAR, check_id UUID, endpoint_id UUID)
+RETURNS VARCHAR AS $$
+    SELECT CASE
+        WHEN status = 'fail' AND EXISTS (
+            SELECT 1 FROM check_waivers w
+            WHERE w.check_id = $2
+              AND (w.endpoint_id IS NULL OR w.endpoint_id = $3)
+              AND w.expires_at > NOW()
+        ) THEN 'waived'
+        ELSE status
+    END
+$$ LANGUAGE SQL STABLE;
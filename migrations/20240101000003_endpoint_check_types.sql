@@ -0,0 +1,3 @@
+-- Check types each agent reported as supported at registration
+
+ALTER TABLE endpoints ADD COLUMN supported_check_types JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,16 @@
+-- Revision counter for check definitions, included in signed check bundles
+-- so agents can reject bundles older than one they have already seen
+
+INSERT INTO server_config (key, value) VALUES ('check_revision', '0')
+ON CONFLICT (key) DO NOTHING;
+
+CREATE FUNCTION bump_check_revision() RETURNS trigger AS $$
+BEGIN
+    UPDATE server_config SET value = (value::BIGINT + 1)::TEXT WHERE key = 'check_revision';
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER check_definitions_revision
+AFTER INSERT OR UPDATE OR DELETE ON check_definitions
+FOR EACH STATEMENT EXECUTE FUNCTION bump_check_revision();
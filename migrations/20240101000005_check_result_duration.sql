@@ -0,0 +1,2 @@
+-- Time the agent spent executing each check
+ALTER TABLE check_results ADD COLUMN duration_ms BIGINT;
@@ -0,0 +1,2 @@
+-- Optional per-check schedule; NULL runs the check on every collection cycle
+ALTER TABLE check_definitions ADD COLUMN schedule JSONB;
@@ -0,0 +1,5 @@
+-- Structured evidence and measured value reported with each check result
+ALTER TABLE check_results ADD COLUMN evidence JSONB;
+ALTER TABLE check_results ADD COLUMN value DOUBLE PRECISION;
+
+CREATE INDEX idx_check_results_evidence ON check_results USING GIN (evidence jsonb_path_ops);
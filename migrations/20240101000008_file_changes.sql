@@ -0,0 +1,26 @@
+-- File integrity monitoring: files that drifted from each agent's baseline
+
+CREATE TABLE file_changes (
+    id UUID PRIMARY KEY,
+    endpoint_id UUID NOT NULL REFERENCES endpoints(id) ON DELETE CASCADE,
+    path TEXT NOT NULL,
+    change_type VARCHAR(20) NOT NULL,
+    baseline_state JSONB,
+    current_state JSONB,
+    -- 'open' while the agent reports it, then 'accepted' or 'reverted'
+    status VARCHAR(20) NOT NULL DEFAULT 'open',
+    first_seen_at TIMESTAMPTZ NOT NULL,
+    last_seen_at TIMESTAMPTZ NOT NULL,
+    resolved_at TIMESTAMPTZ,
+    resolved_by VARCHAR(100),
+    -- When an accepted change was handed to the agent to update its baseline
+    synced_at TIMESTAMPTZ
+);
+
+-- A file has at most one open change per endpoint; reports update it in place
+CREATE UNIQUE INDEX idx_file_changes_open ON file_changes(endpoint_id, path) WHERE status = 'open';
+CREATE INDEX idx_file_changes_endpoint_id ON file_changes(endpoint_id, last_seen_at DESC);
+
+-- Latest file integrity scan reported by each agent
+ALTER TABLE endpoints ADD COLUMN fim_scanned_at TIMESTAMPTZ;
+ALTER TABLE endpoints ADD COLUMN fim_files_scanned BIGINT;
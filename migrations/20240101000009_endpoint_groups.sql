@@ -0,0 +1,40 @@
+-- Named groups of endpoints, so waivers can cover several endpoints at once
+
+CREATE TABLE endpoint_groups (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE endpoint_group_members (
+    group_id UUID NOT NULL REFERENCES endpoint_groups(id) ON DELETE CASCADE,
+    endpoint_id UUID NOT NULL REFERENCES endpoints(id) ON DELETE CASCADE,
+    PRIMARY KEY (group_id, endpoint_id)
+);
+
+CREATE INDEX idx_endpoint_group_members_endpoint_id ON endpoint_group_members(endpoint_id);
+
+-- A waiver covers one endpoint, one group, or with neither every endpoint
+ALTER TABLE check_waivers ADD COLUMN group_id UUID REFERENCES endpoint_groups(id) ON DELETE CASCADE;
+ALTER TABLE check_waivers ADD CONSTRAINT check_waivers_single_scope
+    CHECK (endpoint_id IS NULL OR group_id IS NULL);
+
+CREATE OR REPLACE FUNCTION effective_check_status(status VARCHAR, check_id UUID, endpoint_id UUID)
+RETURNS VARCHAR AS $$
+    SELECT CASE
+        WHEN status = 'fail' AND EXISTS (
+            SELECT 1 FROM check_waivers w
+            WHERE w.check_id = $2
+              AND w.expires_at > NOW()
+              AND CASE
+                  WHEN w.endpoint_id IS NOT NULL THEN w.endpoint_id = $3
+                  WHEN w.group_id IS NOT NULL THEN EXISTS (
+                      SELECT 1 FROM endpoint_group_members m
+                      WHERE m.group_id = w.group_id AND m.endpoint_id = $3
+                  )
+                  ELSE TRUE
+              END
+        ) THEN 'waived'
+        ELSE status
+    END
+$$ LANGUAGE SQL STABLE;
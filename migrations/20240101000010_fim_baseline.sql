@@ -0,0 +1,4 @@
+-- When each agent last took a new file baseline. Open changes first seen
+-- before it cannot be judged against the new baseline, so reports never mark
+-- them reverted
+ALTER TABLE endpoints ADD COLUMN fim_baseline_at TIMESTAMPTZ;
@@ -2,13 +2,18 @@ use axum::{
     extract::{Path, State, Query},
     Json,
 };
-use common::{CheckStatus, DashboardSummary, Endpoint, RecentCheckResult, Severity, SystemSnapshot};
+use chrono::{DateTime, Utc};
+use common::{
+    CheckSchedule, CheckStatus, CheckType, DashboardSummary, Endpoint, FieldError,
+    FileChangeKind, FileChangeStatus, FileState, RecentCheckResult, Severity, SystemSnapshot,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::api::ApiError;
 use crate::AppState;
-use crate::db::{checks, endpoints, results, snapshots};
+use crate::db::{checks, endpoints, file_changes, groups, results, snapshots, waivers};
+use crate::services::status;
 
 // Endpoints
 
@@ -37,7 +42,10 @@ pub async fn get_endpoint(
             check_name: r.check_name,
             status: r.status.parse().unwrap_or(CheckStatus::Error),
             message: r.message,
+            evidence: r.evidence,
+            value: r.value,
             collected_at: r.collected_at.to_rfc3339(),
+            duration_ms: r.duration_ms,
         })
         .collect();
 
@@ -61,7 +69,10 @@ pub struct EndpointCheckResult {
     pub check_name: String,
     pub status: CheckStatus,
     pub message: Option<String>,
+    pub evidence: Option<serde_json::Value>,
+    pub value: Option<f64>,
     pub collected_at: String,
+    pub duration_ms: Option<i64>,
 }
 
 pub async fn delete_endpoint(
@@ -95,17 +106,7 @@ pub async fn list_checks(
 
     let response: Vec<CheckDefinitionResponse> = check_list
         .into_iter()
-        .map(|c| CheckDefinitionResponse {
-            id: c.id,
-            name: c.name,
-            description: c.description,
-            check_type: c.check_type,
-            parameters: c.parameters,
-            severity: c.severity.parse().unwrap_or(Severity::Medium),
-            enabled: c.enabled,
-            created_at: c.created_at.to_rfc3339(),
-            updated_at: c.updated_at.to_rfc3339(),
-        })
+        .map(CheckDefinitionResponse::from)
         .collect();
 
     Ok(Json(response))
@@ -119,11 +120,29 @@ pub struct CheckDefinitionResponse {
     pub check_type: String,
     pub parameters: serde_json::Value,
     pub severity: Severity,
+    pub schedule: Option<CheckSchedule>,
     pub enabled: bool,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl From<checks::CheckDefinitionRow> for CheckDefinitionResponse {
+    fn from(c: checks::CheckDefinitionRow) -> Self {
+        Self {
+            schedule: c.schedule(),
+            id: c.id,
+            name: c.name,
+            description: c.description,
+            check_type: c.check_type,
+            parameters: c.parameters,
+            severity: c.severity.parse().unwrap_or(Severity::Medium),
+            enabled: c.enabled,
+            created_at: c.created_at.to_rfc3339(),
+            updated_at: c.updated_at.to_rfc3339(),
+        }
+    }
+}
+
 pub async fn get_check(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -132,17 +151,7 @@ pub async fn get_check(
         .await?
         .ok_or_else(|| ApiError::not_found("Check not found"))?;
 
-    Ok(Json(CheckDefinitionResponse {
-        id: check.id,
-        name: check.name,
-        description: check.description,
-        check_type: check.check_type,
-        parameters: check.parameters,
-        severity: check.severity.parse().unwrap_or(Severity::Medium),
-        enabled: check.enabled,
-        created_at: check.created_at.to_rfc3339(),
-        updated_at: check.updated_at.to_rfc3339(),
-    }))
+    Ok(Json(CheckDefinitionResponse::from(check)))
 }
 
 #[derive(Debug, Deserialize)]
@@ -153,6 +162,8 @@ pub struct CreateCheckRequest {
     pub parameters: serde_json::Value,
     #[serde(default)]
     pub severity: Option<Severity>,
+    #[serde(default)]
+    pub schedule: Option<CheckSchedule>,
     #[serde(default = "default_true")]
     pub enabled: bool,
 }
@@ -161,34 +172,62 @@ fn default_true() -> bool {
     true
 }
 
+/// JSON Schema of every check type and its parameters
+pub async fn get_check_schema() -> Json<schemars::schema::RootSchema> {
+    Json(common::check_schema())
+}
+
+fn validate_check_request(
+    name: &str,
+    check_type: &str,
+    parameters: &serde_json::Value,
+    schedule: Option<&CheckSchedule>,
+) -> Result<CheckType, ApiError> {
+    let mut fields = Vec::new();
+
+    if name.trim().is_empty() {
+        fields.push(FieldError::new("name", "must not be empty"));
+    }
+    if let Some(Err(e)) = schedule.map(CheckSchedule::validate) {
+        fields.push(FieldError::new("schedule", e));
+    }
+
+    match CheckType::from_parts(check_type, parameters) {
+        Ok(check_type) if fields.is_empty() => Ok(check_type),
+        Ok(_) => Err(ApiError::validation(fields)),
+        Err(errors) => {
+            fields.extend(errors);
+            Err(ApiError::validation(fields))
+        }
+    }
+}
+
 pub async fn create_check(
     State(state): State<AppState>,
     Json(req): Json<CreateCheckRequest>,
 ) -> Result<Json<CheckDefinitionResponse>, ApiError> {
     let severity = req.severity.unwrap_or(Severity::Medium);
+    let check_type = validate_check_request(
+        &req.name,
+        &req.check_type,
+        &req.parameters,
+        req.schedule.as_ref(),
+    )?;
 
     let check = checks::create_check(
         &state.pool,
-        &req.name,
-        req.description.as_deref(),
-        &req.check_type,
-        req.parameters,
-        severity,
-        req.enabled,
+        &checks::CheckFields {
+            name: &req.name,
+            description: req.description.as_deref(),
+            check_type: &check_type,
+            severity,
+            schedule: req.schedule.as_ref(),
+            enabled: req.enabled,
+        },
     )
     .await?;
 
-    Ok(Json(CheckDefinitionResponse {
-        id: check.id,
-        name: check.name,
-        description: check.description,
-        check_type: check.check_type,
-        parameters: check.parameters,
-        severity: check.severity.parse().unwrap_or(Severity::Medium),
-        enabled: check.enabled,
-        created_at: check.created_at.to_rfc3339(),
-        updated_at: check.updated_at.to_rfc3339(),
-    }))
+    Ok(Json(CheckDefinitionResponse::from(check)))
 }
 
 #[derive(Debug, Deserialize)]
@@ -198,6 +237,8 @@ pub struct UpdateCheckRequest {
     pub check_type: String,
     pub parameters: serde_json::Value,
     pub severity: Severity,
+    #[serde(default)]
+    pub schedule: Option<CheckSchedule>,
     pub enabled: bool,
 }
 
@@ -206,30 +247,31 @@ pub async fn update_check(
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateCheckRequest>,
 ) -> Result<Json<CheckDefinitionResponse>, ApiError> {
+    let check_type = validate_check_request(
+        &req.name,
+        &req.check_type,
+        &req.parameters,
+        req.schedule.as_ref(),
+    )?;
+
     let check = checks::update_check(
         &state.pool,
         id,
-        &req.name,
-        req.description.as_deref(),
-        &req.check_type,
-        req.parameters,
-        req.severity,
-        req.enabled,
+        &checks::CheckFields {
+            name: &req.name,
+            description: req.description.as_deref(),
+            check_type: &check_type,
+            severity: req.severity,
+            schedule: req.schedule.as_ref(),
+            enabled: req.enabled,
+        },
     )
     .await?
     .ok_or_else(|| ApiError::not_found("Check not found"))?;
 
-    Ok(Json(CheckDefinitionResponse {
-        id: check.id,
-        name: check.name,
-        description: check.description,
-        check_type: check.check_type,
-        parameters: check.parameters,
-        severity: check.severity.parse().unwrap_or(Severity::Medium),
-        enabled: check.enabled,
-        created_at: check.created_at.to_rfc3339(),
-        updated_at: check.updated_at.to_rfc3339(),
-    }))
+    status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await?;
+
+    Ok(Json(CheckDefinitionResponse::from(check)))
 }
 
 pub async fn delete_check(
@@ -239,6 +281,7 @@ pub async fn delete_check(
     let deleted = checks::delete_check(&state.pool, id).await?;
 
     if deleted {
+        status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await?;
         Ok(Json(DeleteResponse {
             success: true,
             message: "Check deleted".to_string(),
@@ -254,6 +297,14 @@ pub async fn delete_check(
 pub struct ResultsQuery {
     pub endpoint_id: Option<Uuid>,
     pub check_id: Option<Uuid>,
+    pub status: Option<CheckStatus>,
+    /// JSON object the result evidence must contain, e.g. `{"observed":"yes"}`
+    pub evidence: Option<String>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    /// Only the latest result of each check on each endpoint
+    #[serde(default)]
+    pub latest: bool,
     #[serde(default = "default_limit")]
     pub limit: i64,
 }
@@ -266,41 +317,39 @@ pub async fn list_results(
     State(state): State<AppState>,
     Query(query): Query<ResultsQuery>,
 ) -> Result<Json<Vec<ResultResponse>>, ApiError> {
-    let result_rows = if let Some(endpoint_id) = query.endpoint_id {
-        results::get_results_for_endpoint(&state.pool, endpoint_id, query.limit).await?
-    } else if let Some(check_id) = query.check_id {
-        results::get_results_for_check(&state.pool, check_id, query.limit).await?
-    } else {
-        // Return recent results
-        let recent = results::get_recent_results(&state.pool, query.limit).await?;
-        return Ok(Json(
-            recent
-                .into_iter()
-                .map(|r| ResultResponse {
-                    id: r.id,
-                    endpoint_id: None,
-                    endpoint_hostname: Some(r.endpoint_hostname),
-                    check_id: None,
-                    check_name: Some(r.check_name),
-                    status: r.status.parse().unwrap_or(CheckStatus::Error),
-                    message: r.message,
-                    collected_at: r.collected_at.to_rfc3339(),
-                })
-                .collect(),
-        ));
+    let evidence = match &query.evidence {
+        Some(raw) => Some(serde_json::from_str(raw).map_err(|e| {
+            ApiError::validation(vec![FieldError::new("evidence", format!("invalid JSON: {}", e))])
+        })?),
+        None => None,
+    };
+
+    let filter = results::ResultFilter {
+        endpoint_id: query.endpoint_id,
+        check_id: query.check_id,
+        status: query.status,
+        evidence,
+        min_value: query.min_value,
+        max_value: query.max_value,
+        latest: query.latest,
+        limit: query.limit,
     };
 
-    let response: Vec<ResultResponse> = result_rows
+    let response: Vec<ResultResponse> = results::search_results(&state.pool, &filter)
+        .await?
         .into_iter()
         .map(|r| ResultResponse {
             id: r.id,
-            endpoint_id: Some(r.endpoint_id),
-            endpoint_hostname: None,
-            check_id: Some(r.check_id),
-            check_name: None,
+            endpoint_id: r.endpoint_id,
+            endpoint_hostname: r.endpoint_hostname,
+            check_id: r.check_id,
+            check_name: r.check_name,
             status: r.status.parse().unwrap_or(CheckStatus::Error),
             message: r.message,
+            evidence: r.evidence,
+            value: r.value,
             collected_at: r.collected_at.to_rfc3339(),
+            duration_ms: r.duration_ms,
         })
         .collect();
 
@@ -310,13 +359,345 @@ pub async fn list_results(
 #[derive(Debug, Serialize)]
 pub struct ResultResponse {
     pub id: Uuid,
-    pub endpoint_id: Option<Uuid>,
-    pub endpoint_hostname: Option<String>,
-    pub check_id: Option<Uuid>,
-    pub check_name: Option<String>,
+    pub endpoint_id: Uuid,
+    pub endpoint_hostname: String,
+    pub check_id: Uuid,
+    pub check_name: String,
     pub status: CheckStatus,
     pub message: Option<String>,
+    pub evidence: Option<serde_json::Value>,
+    pub value: Option<f64>,
     pub collected_at: String,
+    pub duration_ms: Option<i64>,
+}
+
+// File changes
+
+#[derive(Debug, Deserialize)]
+pub struct FileChangesQuery {
+    pub status: Option<FileChangeStatus>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileChangeResponse {
+    pub id: Uuid,
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub status: FileChangeStatus,
+    pub baseline: Option<FileState>,
+    pub current: Option<FileState>,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+    pub resolved_at: Option<String>,
+    pub resolved_by: Option<String>,
+}
+
+impl From<file_changes::FileChangeRow> for FileChangeResponse {
+    fn from(c: file_changes::FileChangeRow) -> Self {
+        Self {
+            kind: c.change_type.parse().unwrap_or(FileChangeKind::Modified),
+            status: c.status.parse().unwrap_or(FileChangeStatus::Open),
+            baseline: c.baseline_state(),
+            current: c.current_state(),
+            id: c.id,
+            path: c.path,
+            first_seen_at: c.first_seen_at.to_rfc3339(),
+            last_seen_at: c.last_seen_at.to_rfc3339(),
+            resolved_at: c.resolved_at.map(|t| t.to_rfc3339()),
+            resolved_by: c.resolved_by,
+        }
+    }
+}
+
+pub async fn list_file_changes(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<FileChangesQuery>,
+) -> Result<Json<Vec<FileChangeResponse>>, ApiError> {
+    if endpoints::get_endpoint_by_id(&state.pool, id).await?.is_none() {
+        return Err(ApiError::not_found("Endpoint not found"));
+    }
+
+    let status = query.status.map(|s| s.to_string());
+    let changes = file_changes::list_changes(
+        &state.pool,
+        id,
+        status.as_deref(),
+        query.limit.clamp(1, 1000),
+    )
+    .await?;
+
+    Ok(Json(changes.into_iter().map(FileChangeResponse::from).collect()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptFileChangesRequest {
+    pub accepted_by: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AcceptFileChangesResponse {
+    pub accepted: u64,
+}
+
+/// Accept every open change into the endpoint's baseline; the agent picks
+/// them up with its next scan
+pub async fn accept_file_changes(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<AcceptFileChangesRequest>,
+) -> Result<Json<AcceptFileChangesResponse>, ApiError> {
+    if req.accepted_by.trim().is_empty() {
+        return Err(ApiError::bad_request("Approver is required"));
+    }
+    if endpoints::get_endpoint_by_id(&state.pool, id).await?.is_none() {
+        return Err(ApiError::not_found("Endpoint not found"));
+    }
+
+    let accepted =
+        file_changes::accept_open_changes(&state.pool, id, req.accepted_by.trim()).await?;
+
+    Ok(Json(AcceptFileChangesResponse { accepted }))
+}
+
+// Endpoint groups
+
+#[derive(Debug, Serialize)]
+pub struct GroupResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub endpoint_ids: Vec<Uuid>,
+    pub hostnames: Vec<String>,
+    pub created_at: String,
+}
+
+impl From<groups::GroupRow> for GroupResponse {
+    fn from(g: groups::GroupRow) -> Self {
+        Self {
+            id: g.id,
+            name: g.name,
+            endpoint_ids: g.endpoint_ids,
+            hostnames: g.hostnames,
+            created_at: g.created_at.to_rfc3339(),
+        }
+    }
+}
+
+pub async fn list_groups(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<GroupResponse>>, ApiError> {
+    let group_list = groups::list_groups(&state.pool).await?;
+    Ok(Json(group_list.into_iter().map(GroupResponse::from).collect()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGroupRequest {
+    pub name: String,
+    #[serde(default)]
+    pub endpoint_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetGroupMembersRequest {
+    pub endpoint_ids: Vec<Uuid>,
+}
+
+async fn check_endpoints_exist(state: &AppState, endpoint_ids: &[Uuid]) -> Result<(), ApiError> {
+    for endpoint_id in endpoint_ids {
+        if endpoints::get_endpoint_by_id(&state.pool, *endpoint_id).await?.is_none() {
+            return Err(ApiError::bad_request(format!("Endpoint {} not found", endpoint_id)));
+        }
+    }
+    Ok(())
+}
+
+pub async fn create_group(
+    State(state): State<AppState>,
+    Json(req): Json<CreateGroupRequest>,
+) -> Result<Json<GroupResponse>, ApiError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Name is required"));
+    }
+    check_endpoints_exist(&state, &req.endpoint_ids).await?;
+
+    let id = match groups::create_group(&state.pool, name, &req.endpoint_ids).await {
+        Ok(id) => id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err(ApiError::bad_request("A group with that name already exists"));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let group = groups::get_group_by_id(&state.pool, id)
+        .await?
+        .ok_or_else(|| ApiError::internal("Group not found after creation"))?;
+
+    Ok(Json(GroupResponse::from(group)))
+}
+
+/// Replace a group's members; waivers scoped to the group follow them
+pub async fn set_group_members(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetGroupMembersRequest>,
+) -> Result<Json<GroupResponse>, ApiError> {
+    check_endpoints_exist(&state, &req.endpoint_ids).await?;
+
+    if !groups::set_group_members(&state.pool, id, &req.endpoint_ids).await? {
+        return Err(ApiError::not_found("Group not found"));
+    }
+    status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await?;
+
+    let group = groups::get_group_by_id(&state.pool, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Group not found"))?;
+
+    Ok(Json(GroupResponse::from(group)))
+}
+
+/// Delete a group along with the waivers scoped to it
+pub async fn delete_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DeleteResponse>, ApiError> {
+    let deleted = groups::delete_group(&state.pool, id).await?;
+
+    if deleted {
+        status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await?;
+        Ok(Json(DeleteResponse {
+            success: true,
+            message: "Group deleted".to_string(),
+        }))
+    } else {
+        Err(ApiError::not_found("Group not found"))
+    }
+}
+
+// Waivers
+
+pub async fn list_waivers(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<WaiverResponse>>, ApiError> {
+    let waiver_list = waivers::list_waivers(&state.pool).await?;
+    Ok(Json(waiver_list.into_iter().map(WaiverResponse::from).collect()))
+}
+
+#[derive(Debug, Serialize)]
+pub struct WaiverResponse {
+    pub id: Uuid,
+    pub check_id: Uuid,
+    pub check_name: String,
+    pub endpoint_id: Option<Uuid>,
+    pub endpoint_hostname: Option<String>,
+    pub group_id: Option<Uuid>,
+    pub group_name: Option<String>,
+    pub justification: String,
+    pub approved_by: String,
+    pub expires_at: String,
+    pub active: bool,
+    pub created_at: String,
+}
+
+impl From<waivers::WaiverRow> for WaiverResponse {
+    fn from(w: waivers::WaiverRow) -> Self {
+        Self {
+            active: w.is_active(),
+            id: w.id,
+            check_id: w.check_id,
+            check_name: w.check_name,
+            endpoint_id: w.endpoint_id,
+            endpoint_hostname: w.endpoint_hostname,
+            group_id: w.group_id,
+            group_name: w.group_name,
+            justification: w.justification,
+            approved_by: w.approved_by,
+            expires_at: w.expires_at.to_rfc3339(),
+            created_at: w.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWaiverRequest {
+    pub check_id: Uuid,
+    /// Omit both `endpoint_id` and `group_id` to waive the check on every endpoint
+    pub endpoint_id: Option<Uuid>,
+    pub group_id: Option<Uuid>,
+    pub justification: String,
+    pub approved_by: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+pub async fn create_waiver(
+    State(state): State<AppState>,
+    Json(req): Json<CreateWaiverRequest>,
+) -> Result<Json<WaiverResponse>, ApiError> {
+    if req.justification.trim().is_empty() {
+        return Err(ApiError::bad_request("Justification is required"));
+    }
+    if req.approved_by.trim().is_empty() {
+        return Err(ApiError::bad_request("Approver is required"));
+    }
+    if req.expires_at <= Utc::now() {
+        return Err(ApiError::bad_request("Expiry must be in the future"));
+    }
+    if checks::get_check_by_id(&state.pool, req.check_id).await?.is_none() {
+        return Err(ApiError::bad_request("Check not found"));
+    }
+    if let Some(endpoint_id) = req.endpoint_id {
+        if endpoints::get_endpoint_by_id(&state.pool, endpoint_id).await?.is_none() {
+            return Err(ApiError::bad_request("Endpoint not found"));
+        }
+    }
+    if let Some(group_id) = req.group_id {
+        if req.endpoint_id.is_some() {
+            return Err(ApiError::bad_request(
+                "A waiver applies to an endpoint or a group, not both",
+            ));
+        }
+        if groups::get_group_by_id(&state.pool, group_id).await?.is_none() {
+            return Err(ApiError::bad_request("Group not found"));
+        }
+    }
+
+    let id = waivers::create_waiver(
+        &state.pool,
+        req.check_id,
+        req.endpoint_id,
+        req.group_id,
+        req.justification.trim(),
+        req.approved_by.trim(),
+        req.expires_at,
+    )
+    .await?;
+
+    status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await?;
+
+    let waiver = waivers::get_waiver_by_id(&state.pool, id)
+        .await?
+        .ok_or_else(|| ApiError::internal("Waiver not found after creation"))?;
+
+    Ok(Json(WaiverResponse::from(waiver)))
+}
+
+pub async fn delete_waiver(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DeleteResponse>, ApiError> {
+    let deleted = waivers::delete_waiver(&state.pool, id).await?;
+
+    if deleted {
+        status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await?;
+        Ok(Json(DeleteResponse {
+            success: true,
+            message: "Waiver deleted".to_string(),
+        }))
+    } else {
+        Err(ApiError::not_found("Waiver not found"))
+    }
 }
 
 // Dashboard summary
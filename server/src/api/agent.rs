@@ -4,15 +4,17 @@ use axum::{
     Json,
 };
 use chrono::Utc;
+use common::signing;
 use common::{
-    AgentCheckDefinition, CheckStatus, ChecksResponse, EndpointStatus,
-    HeartbeatRequest, HeartbeatResponse, RegisterRequest, RegisterResponse, Severity,
-    SubmitResultsRequest, SubmitResultsResponse,
+    AcceptedFileChange, AgentCheckDefinition, CheckStatus, ChecksResponse, HeartbeatRequest,
+    HeartbeatResponse, RegisterRequest, RegisterResponse, Severity, SubmitFileChangesRequest,
+    SubmitFileChangesResponse, SubmitResultsRequest, SubmitResultsResponse,
 };
 
 use crate::api::ApiError;
 use crate::AppState;
-use crate::db::{checks, endpoints, results, snapshots};
+use crate::db::{checks, endpoints, file_changes, results, snapshots};
+use crate::services::status;
 
 const AGENT_SECRET_HEADER: &str = "x-agent-secret";
 
@@ -48,6 +50,9 @@ pub async fn register(
     )
     .await?;
 
+    endpoints::update_supported_check_types(&state.pool, endpoint.id, &req.supported_check_types)
+        .await?;
+
     Ok(Json(RegisterResponse {
         endpoint_id: endpoint.id,
         message: "Registration successful".to_string(),
@@ -84,8 +89,14 @@ pub async fn heartbeat(
     )
     .await?;
 
-    // Update endpoint status
-    endpoints::update_endpoint_heartbeat(&state.pool, req.endpoint_id, EndpointStatus::Online).await?;
+    // Update endpoint status from the latest check results
+    let new_status = status::compute_endpoint_status(
+        &state.pool,
+        req.endpoint_id,
+        state.config.check_error_policy,
+    )
+    .await?;
+    endpoints::update_endpoint_heartbeat(&state.pool, req.endpoint_id, new_status).await?;
 
     Ok(Json(HeartbeatResponse {
         status: "ok".to_string(),
@@ -99,20 +110,42 @@ pub async fn get_checks(
 ) -> Result<Json<ChecksResponse>, ApiError> {
     verify_agent_secret(&headers, &state.config.agent_secret)?;
 
+    let revision = checks::get_check_revision(&state.pool).await?;
     let check_rows = checks::list_enabled_checks(&state.pool).await?;
 
     let checks: Vec<AgentCheckDefinition> = check_rows
         .into_iter()
-        .map(|row| AgentCheckDefinition {
-            id: row.id,
-            name: row.name,
-            check_type: row.check_type,
-            parameters: row.parameters,
-            severity: row.severity.parse().unwrap_or(Severity::Medium),
+        .map(|row| {
+            let schedule = row.schedule();
+            let mut check = AgentCheckDefinition {
+                id: row.id,
+                name: row.name,
+                check_type: row.check_type,
+                parameters: row.parameters,
+                severity: row.severity.parse().unwrap_or(Severity::Medium),
+                schedule,
+                signature: None,
+            };
+            if let Some(key) = &state.signing_key {
+                check.signature = Some(signing::sign(key, &check.signing_payload()));
+            }
+            check
         })
         .collect();
 
-    Ok(Json(ChecksResponse { checks }))
+    let issued_at = Utc::now();
+    let mut response = ChecksResponse {
+        checks,
+        revision,
+        issued_at,
+        expires_at: issued_at + chrono::Duration::seconds(state.config.check_bundle_ttl_secs),
+        signature: None,
+    };
+    if let Some(key) = &state.signing_key {
+        signing::sign_checks_response(key, &mut response);
+    }
+
+    Ok(Json(response))
 }
 
 pub async fn submit_results(
@@ -135,24 +168,16 @@ pub async fn submit_results(
     );
 
     let mut accepted = 0;
-    let mut has_failures = false;
-
-    for result in &req.results {
-        match results::create_result(
-            &state.pool,
-            req.endpoint_id,
-            result.check_id,
-            result.status,
-            result.message.as_deref(),
-            result.collected_at,
-        )
-        .await
-        {
+
+    for mut result in req.results {
+        // Waived is derived from active waivers on the server, never reported by agents
+        if result.status == CheckStatus::Waived {
+            result.status = CheckStatus::Fail;
+        }
+
+        match results::create_result(&state.pool, req.endpoint_id, &result).await {
             Ok(_) => {
                 accepted += 1;
-                if result.status == CheckStatus::Fail {
-                    has_failures = true;
-                }
             }
             Err(e) => {
                 tracing::warn!("Failed to store check result: {:?}", e);
@@ -160,12 +185,13 @@ pub async fn submit_results(
         }
     }
 
-    // Update endpoint status based on results
-    let new_status = if has_failures {
-        EndpointStatus::Warning
-    } else {
-        EndpointStatus::Online
-    };
+    // Update endpoint status from the latest state of all checks
+    let new_status = status::compute_endpoint_status(
+        &state.pool,
+        req.endpoint_id,
+        state.config.check_error_policy,
+    )
+    .await?;
     endpoints::update_endpoint_heartbeat(&state.pool, req.endpoint_id, new_status).await?;
 
     Ok(Json(SubmitResultsResponse {
@@ -173,3 +199,73 @@ pub async fn submit_results(
         message: format!("Accepted {} results", accepted),
     }))
 }
+
+pub async fn submit_file_changes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SubmitFileChangesRequest>,
+) -> Result<Json<SubmitFileChangesResponse>, ApiError> {
+    verify_agent_secret(&headers, &state.config.agent_secret)?;
+
+    // Verify endpoint exists
+    let endpoint = endpoints::get_endpoint_by_id(&state.pool, req.endpoint_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Endpoint not found"))?;
+
+    tracing::debug!(
+        "Receiving {} file changes from endpoint: {} ({})",
+        req.changes.len(),
+        endpoint.hostname,
+        endpoint.id
+    );
+
+    file_changes::mark_accepted_synced(&state.pool, req.endpoint_id, &req.applied).await?;
+
+    // Accepted changes are handed back until the agent confirms applying them.
+    // It may still report them in this scan, and they must not reopen once it
+    // updates its baseline
+    let accepted: Vec<AcceptedFileChange> =
+        file_changes::list_unsynced_accepted(&state.pool, req.endpoint_id)
+            .await?
+            .into_iter()
+            .map(|row| AcceptedFileChange {
+                state: row.current_state(),
+                id: row.id,
+                path: row.path,
+            })
+            .collect();
+
+    let changes: Vec<_> = req
+        .changes
+        .into_iter()
+        .filter(|change| {
+            !accepted
+                .iter()
+                .any(|a| a.path == change.path && a.state == change.current)
+        })
+        .collect();
+
+    file_changes::record_changes(
+        &state.pool,
+        req.endpoint_id,
+        &changes,
+        req.scanned_at,
+        !req.truncated,
+        req.baseline_reset,
+    )
+    .await?;
+    endpoints::update_fim_scan(
+        &state.pool,
+        req.endpoint_id,
+        req.scanned_at,
+        req.files_scanned as i64,
+    )
+    .await?;
+
+    let open_changes = file_changes::count_open_changes(&state.pool, req.endpoint_id).await?;
+
+    Ok(Json(SubmitFileChangesResponse {
+        open_changes: open_changes as usize,
+        accepted,
+    }))
+}
@@ -6,11 +6,12 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use common::ErrorResponse;
+use common::{ErrorResponse, FieldError};
 
 pub struct ApiError {
     status: StatusCode,
     message: String,
+    fields: Vec<FieldError>,
 }
 
 impl ApiError {
@@ -18,6 +19,14 @@ impl ApiError {
         Self {
             status,
             message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn validation(fields: Vec<FieldError>) -> Self {
+        Self {
+            fields,
+            ..Self::new(StatusCode::UNPROCESSABLE_ENTITY, "Validation failed")
         }
     }
 
@@ -40,7 +49,8 @@ impl ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let body = ErrorResponse::new(self.status.to_string(), self.message);
+        let body =
+            ErrorResponse::new(self.status.to_string(), self.message).with_fields(self.fields);
         (self.status, Json(body)).into_response()
     }
 }
@@ -1,3 +1,5 @@
+use anyhow::Context;
+use common::signing::{self, SigningKey};
 use serde::Deserialize;
 use std::net::SocketAddr;
 
@@ -14,6 +16,27 @@ pub struct Config {
     pub session_secret: String,
     #[serde(default = "default_offline_threshold")]
     pub offline_threshold_minutes: i64,
+    #[serde(default)]
+    pub check_error_policy: CheckErrorPolicy,
+    /// File holding the base64 Ed25519 private key used to sign checks sent to agents
+    #[serde(default)]
+    pub check_signing_key_file: Option<String>,
+    /// How long agents may use a check bundle before fetching a new one
+    #[serde(default = "default_check_bundle_ttl")]
+    pub check_bundle_ttl_secs: i64,
+}
+
+/// How check results with status `error` affect the derived endpoint status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckErrorPolicy {
+    /// Errors do not affect endpoint status
+    Ignore,
+    /// Any error marks the endpoint as warning
+    #[default]
+    Warning,
+    /// Errors are treated like failures at the check's severity
+    Severity,
 }
 
 fn default_host() -> String {
@@ -36,6 +59,10 @@ fn default_offline_threshold() -> i64 {
     10
 }
 
+fn default_check_bundle_ttl() -> i64 {
+    3600
+}
+
 impl Config {
     pub fn from_env() -> Result<Self, config::ConfigError> {
         let config = config::Config::builder()
@@ -45,6 +72,19 @@ impl Config {
         config.try_deserialize()
     }
 
+    /// Load the check signing key, if one is configured
+    pub fn load_signing_key(&self) -> anyhow::Result<Option<SigningKey>> {
+        let Some(path) = &self.check_signing_key_file else {
+            return Ok(None);
+        };
+
+        let encoded = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read check signing key {}", path))?;
+        let key = signing::parse_signing_key(&encoded).map_err(anyhow::Error::msg)?;
+
+        Ok(Some(key))
+    }
+
     pub fn socket_addr(&self) -> SocketAddr {
         format!("{}:{}", self.host, self.port)
             .parse()
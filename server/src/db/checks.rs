@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use common::Severity;
+use common::{CheckSchedule, CheckType, Severity};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -11,38 +11,57 @@ pub struct CheckDefinitionRow {
     pub check_type: String,
     pub parameters: serde_json::Value,
     pub severity: String,
+    pub schedule: Option<serde_json::Value>,
     pub enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl CheckDefinitionRow {
+    /// Parsed schedule; `None` runs the check on every collection cycle
+    pub fn schedule(&self) -> Option<CheckSchedule> {
+        self.schedule
+            .as_ref()
+            .and_then(|s| serde_json::from_value(s.clone()).ok())
+    }
+}
+
+/// Editable fields of a check definition, with the type and parameters already validated
+#[derive(Debug)]
+pub struct CheckFields<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub check_type: &'a CheckType,
+    pub severity: Severity,
+    pub schedule: Option<&'a CheckSchedule>,
+    pub enabled: bool,
+}
+
 pub async fn create_check(
     pool: &PgPool,
-    name: &str,
-    description: Option<&str>,
-    check_type: &str,
-    parameters: serde_json::Value,
-    severity: Severity,
-    enabled: bool,
+    check: &CheckFields<'_>,
 ) -> Result<CheckDefinitionRow, sqlx::Error> {
     let id = Uuid::new_v4();
     let now = Utc::now();
-    let severity_str = severity.to_string();
+    let check_type = check.check_type.type_id().to_string();
+    let severity_str = check.severity.to_string();
+    let schedule = check.schedule.and_then(|s| serde_json::to_value(s).ok());
 
     sqlx::query_as!(
         CheckDefinitionRow,
         r#"
-        INSERT INTO check_definitions (id, name, description, check_type, parameters, severity, enabled, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)
-        RETURNING id, name, description, check_type, parameters, severity, enabled, created_at, updated_at
+        INSERT INTO check_definitions (id, name, description, check_type, parameters, severity, schedule, enabled, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9)
+        RETURNING id, name, description, check_type, parameters, severity, schedule, enabled, created_at, updated_at
         "#,
         id,
-        name,
-        description,
+        check.name,
+        check.description,
         check_type,
-        parameters,
+        check.check_type.parameters(),
         severity_str,
-        enabled,
+        schedule,
+        check.enabled,
         now,
     )
     .fetch_one(pool)
@@ -53,7 +72,7 @@ pub async fn get_check_by_id(pool: &PgPool, id: Uuid) -> Result<Option<CheckDefi
     sqlx::query_as!(
         CheckDefinitionRow,
         r#"
-        SELECT id, name, description, check_type, parameters, severity, enabled, created_at, updated_at
+        SELECT id, name, description, check_type, parameters, severity, schedule, enabled, created_at, updated_at
         FROM check_definitions WHERE id = $1
         "#,
         id
@@ -66,7 +85,7 @@ pub async fn list_checks(pool: &PgPool) -> Result<Vec<CheckDefinitionRow>, sqlx:
     sqlx::query_as!(
         CheckDefinitionRow,
         r#"
-        SELECT id, name, description, check_type, parameters, severity, enabled, created_at, updated_at
+        SELECT id, name, description, check_type, parameters, severity, schedule, enabled, created_at, updated_at
         FROM check_definitions ORDER BY name
         "#
     )
@@ -78,7 +97,7 @@ pub async fn list_enabled_checks(pool: &PgPool) -> Result<Vec<CheckDefinitionRow
     sqlx::query_as!(
         CheckDefinitionRow,
         r#"
-        SELECT id, name, description, check_type, parameters, severity, enabled, created_at, updated_at
+        SELECT id, name, description, check_type, parameters, severity, schedule, enabled, created_at, updated_at
         FROM check_definitions WHERE enabled = true ORDER BY name
         "#
     )
@@ -86,18 +105,28 @@ pub async fn list_enabled_checks(pool: &PgPool) -> Result<Vec<CheckDefinitionRow
     .await
 }
 
+/// Current revision of the check definitions, bumped by a trigger on every change
+pub async fn get_check_revision(pool: &PgPool) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT value::BIGINT as "revision!" FROM server_config WHERE key = 'check_revision'
+        "#
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| r.revision).unwrap_or(0))
+}
+
 pub async fn update_check(
     pool: &PgPool,
     id: Uuid,
-    name: &str,
-    description: Option<&str>,
-    check_type: &str,
-    parameters: serde_json::Value,
-    severity: Severity,
-    enabled: bool,
+    check: &CheckFields<'_>,
 ) -> Result<Option<CheckDefinitionRow>, sqlx::Error> {
     let now = Utc::now();
-    let severity_str = severity.to_string();
+    let check_type = check.check_type.type_id().to_string();
+    let severity_str = check.severity.to_string();
+    let schedule = check.schedule.and_then(|s| serde_json::to_value(s).ok());
 
     sqlx::query_as!(
         CheckDefinitionRow,
@@ -108,18 +137,20 @@ pub async fn update_check(
             check_type = $4,
             parameters = $5,
             severity = $6,
-            enabled = $7,
-            updated_at = $8
+            schedule = $7,
+            enabled = $8,
+            updated_at = $9
         WHERE id = $1
-        RETURNING id, name, description, check_type, parameters, severity, enabled, created_at, updated_at
+        RETURNING id, name, description, check_type, parameters, severity, schedule, enabled, created_at, updated_at
         "#,
         id,
-        name,
-        description,
+        check.name,
+        check.description,
         check_type,
-        parameters,
+        check.check_type.parameters(),
         severity_str,
-        enabled,
+        schedule,
+        check.enabled,
         now,
     )
     .fetch_optional(pool)
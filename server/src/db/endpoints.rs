@@ -105,6 +105,106 @@ pub async fn update_endpoint_heartbeat(
     Ok(())
 }
 
+pub async fn update_endpoint_status(
+    pool: &PgPool,
+    id: Uuid,
+    status: EndpointStatus,
+) -> Result<(), sqlx::Error> {
+    let status_str = status.to_string();
+
+    sqlx::query!(
+        r#"
+        UPDATE endpoints SET status = $1 WHERE id = $2
+        "#,
+        status_str,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn update_supported_check_types(
+    pool: &PgPool,
+    id: Uuid,
+    check_types: &[String],
+) -> Result<(), sqlx::Error> {
+    let types_json = serde_json::to_value(check_types).unwrap_or_default();
+
+    sqlx::query!(
+        r#"
+        UPDATE endpoints SET supported_check_types = $1 WHERE id = $2
+        "#,
+        types_json,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_supported_check_types(pool: &PgPool, id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT supported_check_types FROM endpoints WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row
+        .and_then(|r| serde_json::from_value(r.supported_check_types).ok())
+        .unwrap_or_default())
+}
+
+/// Latest file integrity scan reported by an endpoint's agent
+#[derive(Debug, Clone)]
+pub struct FimScan {
+    pub scanned_at: DateTime<Utc>,
+    pub files_scanned: i64,
+}
+
+pub async fn update_fim_scan(
+    pool: &PgPool,
+    id: Uuid,
+    scanned_at: DateTime<Utc>,
+    files_scanned: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE endpoints SET fim_scanned_at = $1, fim_files_scanned = $2 WHERE id = $3
+        "#,
+        scanned_at,
+        files_scanned,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_fim_scan(pool: &PgPool, id: Uuid) -> Result<Option<FimScan>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT fim_scanned_at, fim_files_scanned FROM endpoints WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.and_then(|r| {
+        Some(FimScan {
+            scanned_at: r.fim_scanned_at?,
+            files_scanned: r.fim_files_scanned.unwrap_or(0),
+        })
+    }))
+}
+
 pub async fn update_offline_endpoints(pool: &PgPool, threshold_minutes: i64) -> Result<u64, sqlx::Error> {
     let threshold = Utc::now() - chrono::Duration::minutes(threshold_minutes);
 
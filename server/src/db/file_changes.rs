@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use common::{FileChange, FileState};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct FileChangeRow {
+    pub id: Uuid,
+    pub path: String,
+    pub change_type: String,
+    pub baseline_state: Option<serde_json::Value>,
+    pub current_state: Option<serde_json::Value>,
+    pub status: String,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<String>,
+}
+
+impl FileChangeRow {
+    pub fn baseline_state(&self) -> Option<FileState> {
+        self.baseline_state
+            .clone()
+            .and_then(|s| serde_json::from_value(s).ok())
+    }
+
+    pub fn current_state(&self) -> Option<FileState> {
+        self.current_state
+            .clone()
+            .and_then(|s| serde_json::from_value(s).ok())
+    }
+}
+
+/// Store the drift reported by an agent.
+///
+/// Reported files update their open change or open a new one. When
+/// `complete` is set the report covers every drifted file, so open changes
+/// it no longer mentions are marked reverted. When `baseline_reset` is set
+/// the agent took a new baseline, and changes first seen before it stay open
+/// from then on.
+pub async fn record_changes(
+    pool: &PgPool,
+    endpoint_id: Uuid,
+    changes: &[FileChange],
+    seen_at: DateTime<Utc>,
+    complete: bool,
+    baseline_reset: bool,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    if baseline_reset {
+        sqlx::query!(
+            "UPDATE endpoints SET fim_baseline_at = $2 WHERE id = $1",
+            endpoint_id,
+            seen_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for change in changes {
+        let baseline_state = change
+            .baseline
+            .as_ref()
+            .and_then(|s| serde_json::to_value(s).ok());
+        let current_state = change
+            .current
+            .as_ref()
+            .and_then(|s| serde_json::to_value(s).ok());
+
+        sqlx::query!(
+            r#"
+            INSERT INTO file_changes (id, endpoint_id, path, change_type, baseline_state, current_state, status, first_seen_at, last_seen_at)
+            VALUES ($1, $2, $3, $4, $5, $6, 'open', $7, $7)
+            ON CONFLICT (endpoint_id, path) WHERE status = 'open' DO UPDATE SET
+                change_type = EXCLUDED.change_type,
+                baseline_state = EXCLUDED.baseline_state,
+                current_state = EXCLUDED.current_state,
+                last_seen_at = EXCLUDED.last_seen_at
+            "#,
+            Uuid::new_v4(),
+            endpoint_id,
+            change.path,
+            change.kind.to_string(),
+            baseline_state,
+            current_state,
+            seen_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if complete {
+        let paths: Vec<String> = changes.iter().map(|c| c.path.clone()).collect();
+        sqlx::query!(
+            r#"
+            UPDATE file_changes SET status = 'reverted', resolved_at = $3
+            WHERE endpoint_id = $1 AND status = 'open' AND NOT (path = ANY($2))
+              AND first_seen_at >= COALESCE(
+                  (SELECT fim_baseline_at FROM endpoints WHERE id = $1),
+                  '-infinity'
+              )
+            "#,
+            endpoint_id,
+            &paths,
+            seen_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+/// Accepted changes the agent has not confirmed applying to its baseline yet
+pub async fn list_unsynced_accepted(
+    pool: &PgPool,
+    endpoint_id: Uuid,
+) -> Result<Vec<FileChangeRow>, sqlx::Error> {
+    sqlx::query_as!(
+        FileChangeRow,
+        r#"
+        SELECT id, path, change_type, baseline_state, current_state,
+               status, first_seen_at, last_seen_at, resolved_at, resolved_by
+        FROM file_changes
+        WHERE endpoint_id = $1 AND status = 'accepted' AND synced_at IS NULL
+        ORDER BY resolved_at, path
+        "#,
+        endpoint_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Mark accepted changes the agent has applied to its baseline, so they are
+/// no longer handed back
+pub async fn mark_accepted_synced(
+    pool: &PgPool,
+    endpoint_id: Uuid,
+    ids: &[Uuid],
+) -> Result<u64, sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE file_changes SET synced_at = NOW()
+        WHERE endpoint_id = $1 AND status = 'accepted' AND synced_at IS NULL AND id = ANY($2)
+        "#,
+        endpoint_id,
+        ids
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Accept every open change of an endpoint into its baseline
+pub async fn accept_open_changes(
+    pool: &PgPool,
+    endpoint_id: Uuid,
+    accepted_by: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE file_changes SET status = 'accepted', resolved_at = NOW(), resolved_by = $2
+        WHERE endpoint_id = $1 AND status = 'open'
+        "#,
+        endpoint_id,
+        accepted_by
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn count_open_changes(pool: &PgPool, endpoint_id: Uuid) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as count FROM file_changes
+        WHERE endpoint_id = $1 AND status = 'open'
+        "#,
+        endpoint_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.count.unwrap_or(0))
+}
+
+/// Change feed of an endpoint, open changes first, then most recently seen
+pub async fn list_changes(
+    pool: &PgPool,
+    endpoint_id: Uuid,
+    status: Option<&str>,
+    limit: i64,
+) -> Result<Vec<FileChangeRow>, sqlx::Error> {
+    sqlx::query_as!(
+        FileChangeRow,
+        r#"
+        SELECT id, path, change_type, baseline_state, current_state,
+               status, first_seen_at, last_seen_at, resolved_at, resolved_by
+        FROM file_changes
+        WHERE endpoint_id = $1 AND ($2::VARCHAR IS NULL OR status = $2)
+        ORDER BY status = 'open' DESC, last_seen_at DESC
+        LIMIT $3
+        "#,
+        endpoint_id,
+        status,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct GroupRow {
+    pub id: Uuid,
+    pub name: String,
+    pub endpoint_ids: Vec<Uuid>,
+    pub hostnames: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub async fn create_group(
+    pool: &PgPool,
+    name: &str,
+    endpoint_ids: &[Uuid],
+) -> Result<Uuid, sqlx::Error> {
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        "INSERT INTO endpoint_groups (id, name, created_at) VALUES ($1, $2, $3)",
+        id,
+        name,
+        now,
+    )
+    .execute(&mut *tx)
+    .await?;
+    insert_members(&mut tx, id, endpoint_ids).await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Replace the members of a group; returns false if the group does not exist
+pub async fn set_group_members(
+    pool: &PgPool,
+    id: Uuid,
+    endpoint_ids: &[Uuid],
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let exists = sqlx::query!("SELECT id FROM endpoint_groups WHERE id = $1 FOR UPDATE", id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+    if !exists {
+        return Ok(false);
+    }
+
+    sqlx::query!("DELETE FROM endpoint_group_members WHERE group_id = $1", id)
+        .execute(&mut *tx)
+        .await?;
+    insert_members(&mut tx, id, endpoint_ids).await?;
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+async fn insert_members(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    group_id: Uuid,
+    endpoint_ids: &[Uuid],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO endpoint_group_members (group_id, endpoint_id)
+        SELECT $1, endpoint_id FROM UNNEST($2::UUID[]) AS endpoint_id
+        ON CONFLICT DO NOTHING
+        "#,
+        group_id,
+        endpoint_ids,
+    )
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_group_by_id(pool: &PgPool, id: Uuid) -> Result<Option<GroupRow>, sqlx::Error> {
+    sqlx::query_as!(
+        GroupRow,
+        r#"
+        SELECT
+            g.id,
+            g.name,
+            COALESCE(ARRAY_AGG(e.id ORDER BY e.hostname) FILTER (WHERE e.id IS NOT NULL), '{}') as "endpoint_ids!",
+            COALESCE(ARRAY_AGG(e.hostname ORDER BY e.hostname) FILTER (WHERE e.id IS NOT NULL), '{}') as "hostnames!",
+            g.created_at
+        FROM endpoint_groups g
+        LEFT JOIN endpoint_group_members m ON m.group_id = g.id
+        LEFT JOIN endpoints e ON e.id = m.endpoint_id
+        WHERE g.id = $1
+        GROUP BY g.id
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_groups(pool: &PgPool) -> Result<Vec<GroupRow>, sqlx::Error> {
+    sqlx::query_as!(
+        GroupRow,
+        r#"
+        SELECT
+            g.id,
+            g.name,
+            COALESCE(ARRAY_AGG(e.id ORDER BY e.hostname) FILTER (WHERE e.id IS NOT NULL), '{}') as "endpoint_ids!",
+            COALESCE(ARRAY_AGG(e.hostname ORDER BY e.hostname) FILTER (WHERE e.id IS NOT NULL), '{}') as "hostnames!",
+            g.created_at
+        FROM endpoint_groups g
+        LEFT JOIN endpoint_group_members m ON m.group_id = g.id
+        LEFT JOIN endpoints e ON e.id = m.endpoint_id
+        GROUP BY g.id
+        ORDER BY g.name
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn delete_group(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM endpoint_groups WHERE id = $1", id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
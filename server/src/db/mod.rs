@@ -1,8 +1,11 @@
 pub mod endpoints;
 pub mod checks;
+pub mod file_changes;
+pub mod groups;
 pub mod results;
 pub mod snapshots;
 pub mod users;
+pub mod waivers;
 
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use common::CheckStatus;
+use common::{AgentCheckResult, CheckStatus};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -11,82 +11,126 @@ pub struct CheckResultRow {
     pub status: String,
     pub message: Option<String>,
     pub collected_at: DateTime<Utc>,
+    pub duration_ms: Option<i64>,
     pub created_at: DateTime<Utc>,
 }
 
 pub async fn create_result(
     pool: &PgPool,
     endpoint_id: Uuid,
-    check_id: Uuid,
-    status: CheckStatus,
-    message: Option<&str>,
-    collected_at: DateTime<Utc>,
+    result: &AgentCheckResult,
 ) -> Result<CheckResultRow, sqlx::Error> {
     let id = Uuid::new_v4();
     let now = Utc::now();
-    let status_str = status.to_string();
+    let status_str = result.status.to_string();
+    let duration_ms = result.duration_ms.map(|ms| ms as i64);
 
     sqlx::query_as!(
         CheckResultRow,
         r#"
-        INSERT INTO check_results (id, endpoint_id, check_id, status, message, collected_at, created_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
-        RETURNING id, endpoint_id, check_id, status, message, collected_at, created_at
+        INSERT INTO check_results (id, endpoint_id, check_id, status, message, collected_at, duration_ms, evidence, value, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, endpoint_id, check_id, status, message, collected_at, duration_ms, created_at
         "#,
         id,
         endpoint_id,
-        check_id,
+        result.check_id,
         status_str,
-        message,
-        collected_at,
+        result.message.as_deref(),
+        result.collected_at,
+        duration_ms,
+        result.evidence.clone(),
+        result.value,
         now,
     )
     .fetch_one(pool)
     .await
 }
 
-pub async fn get_results_for_endpoint(
-    pool: &PgPool,
-    endpoint_id: Uuid,
-    limit: i64,
-) -> Result<Vec<CheckResultRow>, sqlx::Error> {
-    sqlx::query_as!(
-        CheckResultRow,
-        r#"
-        SELECT id, endpoint_id, check_id, status, message, collected_at, created_at
-        FROM check_results
-        WHERE endpoint_id = $1
-        ORDER BY collected_at DESC
-        LIMIT $2
-        "#,
-        endpoint_id,
-        limit
-    )
-    .fetch_all(pool)
-    .await
+/// Filters for searching check results; unset fields match everything
+#[derive(Debug, Default)]
+pub struct ResultFilter {
+    pub endpoint_id: Option<Uuid>,
+    pub check_id: Option<Uuid>,
+    /// Effective status, so `waived` and `fail` can be told apart
+    pub status: Option<CheckStatus>,
+    /// Results whose evidence contains this JSON (`@>` containment)
+    pub evidence: Option<serde_json::Value>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    /// Only the most recent result of each check on each endpoint
+    pub latest: bool,
+    pub limit: i64,
 }
 
-pub async fn get_results_for_check(
+/// Search results across endpoints and checks, newest first
+pub async fn search_results(
     pool: &PgPool,
-    check_id: Uuid,
-    limit: i64,
-) -> Result<Vec<CheckResultRow>, sqlx::Error> {
+    filter: &ResultFilter,
+) -> Result<Vec<ResultSearchRow>, sqlx::Error> {
+    let status = filter.status.map(|s| s.to_string());
+
     sqlx::query_as!(
-        CheckResultRow,
+        ResultSearchRow,
         r#"
-        SELECT id, endpoint_id, check_id, status, message, collected_at, created_at
-        FROM check_results
-        WHERE check_id = $1
-        ORDER BY collected_at DESC
-        LIMIT $2
+        SELECT
+            cr.id,
+            cr.endpoint_id as "endpoint_id!",
+            e.hostname as endpoint_hostname,
+            cr.check_id as "check_id!",
+            cd.name as check_name,
+            effective_check_status(cr.status, cr.check_id, cr.endpoint_id) as "status!",
+            cr.message,
+            cr.evidence,
+            cr.value,
+            cr.collected_at,
+            cr.duration_ms
+        FROM check_results cr
+        JOIN endpoints e ON e.id = cr.endpoint_id
+        JOIN check_definitions cd ON cd.id = cr.check_id
+        WHERE ($1::UUID IS NULL OR cr.endpoint_id = $1)
+            AND ($2::UUID IS NULL OR cr.check_id = $2)
+            AND ($3::TEXT IS NULL OR effective_check_status(cr.status, cr.check_id, cr.endpoint_id) = $3)
+            AND ($4::JSONB IS NULL OR cr.evidence @> $4)
+            AND ($5::FLOAT8 IS NULL OR cr.value >= $5)
+            AND ($6::FLOAT8 IS NULL OR cr.value <= $6)
+            AND (NOT $7 OR NOT EXISTS (
+                SELECT 1 FROM check_results newer
+                WHERE newer.endpoint_id = cr.endpoint_id
+                    AND newer.check_id = cr.check_id
+                    AND newer.collected_at > cr.collected_at
+            ))
+        ORDER BY cr.collected_at DESC
+        LIMIT $8
         "#,
-        check_id,
-        limit
+        filter.endpoint_id,
+        filter.check_id,
+        status,
+        filter.evidence,
+        filter.min_value,
+        filter.max_value,
+        filter.latest,
+        filter.limit
     )
     .fetch_all(pool)
     .await
 }
 
+#[derive(Debug, Clone)]
+pub struct ResultSearchRow {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    pub endpoint_hostname: String,
+    pub check_id: Uuid,
+    pub check_name: String,
+    pub status: String,
+    pub message: Option<String>,
+    pub evidence: Option<serde_json::Value>,
+    pub value: Option<f64>,
+    pub collected_at: DateTime<Utc>,
+    pub duration_ms: Option<i64>,
+}
+
 pub async fn get_latest_results_for_endpoint(
     pool: &PgPool,
     endpoint_id: Uuid,
@@ -99,9 +143,12 @@ pub async fn get_latest_results_for_endpoint(
             cr.endpoint_id,
             cr.check_id,
             cd.name as check_name,
-            cr.status,
+            effective_check_status(cr.status, cr.check_id, cr.endpoint_id) as "status!",
             cr.message,
-            cr.collected_at
+            cr.collected_at,
+            cr.duration_ms,
+            cr.evidence,
+            cr.value
         FROM check_results cr
         JOIN check_definitions cd ON cd.id = cr.check_id
         WHERE cr.endpoint_id = $1
@@ -122,6 +169,37 @@ pub struct LatestResultRow {
     pub status: String,
     pub message: Option<String>,
     pub collected_at: DateTime<Utc>,
+    pub duration_ms: Option<i64>,
+    pub evidence: Option<serde_json::Value>,
+    pub value: Option<f64>,
+}
+
+/// Latest result status of every enabled check for an endpoint, with the check severity
+pub async fn get_latest_check_states_for_endpoint(
+    pool: &PgPool,
+    endpoint_id: Uuid,
+) -> Result<Vec<LatestCheckStateRow>, sqlx::Error> {
+    sqlx::query_as!(
+        LatestCheckStateRow,
+        r#"
+        SELECT DISTINCT ON (cr.check_id)
+            effective_check_status(cr.status, cr.check_id, cr.endpoint_id) as "status!",
+            cd.severity
+        FROM check_results cr
+        JOIN check_definitions cd ON cd.id = cr.check_id
+        WHERE cr.endpoint_id = $1 AND cd.enabled = true
+        ORDER BY cr.check_id, cr.collected_at DESC
+        "#,
+        endpoint_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[derive(Debug, Clone)]
+pub struct LatestCheckStateRow {
+    pub status: String,
+    pub severity: Option<String>,
 }
 
 pub async fn get_recent_results(pool: &PgPool, limit: i64) -> Result<Vec<RecentResultRow>, sqlx::Error> {
@@ -132,7 +210,7 @@ pub async fn get_recent_results(pool: &PgPool, limit: i64) -> Result<Vec<RecentR
             cr.id,
             e.hostname as endpoint_hostname,
             cd.name as check_name,
-            cr.status,
+            effective_check_status(cr.status, cr.check_id, cr.endpoint_id) as "status!",
             cr.message,
             cr.collected_at
         FROM check_results cr
@@ -164,9 +242,13 @@ pub async fn get_result_stats(pool: &PgPool) -> Result<ResultStats, sqlx::Error>
             COUNT(*) as total,
             COUNT(*) FILTER (WHERE status = 'pass') as passed,
             COUNT(*) FILTER (WHERE status = 'fail') as failed,
-            COUNT(*) FILTER (WHERE status = 'error') as errors
-        FROM check_results
-        WHERE collected_at > NOW() - INTERVAL '24 hours'
+            COUNT(*) FILTER (WHERE status = 'error') as errors,
+            COUNT(*) FILTER (WHERE status = 'waived') as waived
+        FROM (
+            SELECT effective_check_status(status, check_id, endpoint_id) as status
+            FROM check_results
+            WHERE collected_at > NOW() - INTERVAL '24 hours'
+        ) effective
         "#
     )
     .fetch_one(pool)
@@ -177,6 +259,7 @@ pub async fn get_result_stats(pool: &PgPool) -> Result<ResultStats, sqlx::Error>
         passed: row.passed.unwrap_or(0),
         failed: row.failed.unwrap_or(0),
         errors: row.errors.unwrap_or(0),
+        waived: row.waived.unwrap_or(0),
     })
 }
 
@@ -186,4 +269,5 @@ pub struct ResultStats {
     pub passed: i64,
     pub failed: i64,
     pub errors: i64,
+    pub waived: i64,
 }
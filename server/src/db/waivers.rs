@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct WaiverRow {
+    pub id: Uuid,
+    pub check_id: Uuid,
+    pub check_name: String,
+    pub endpoint_id: Option<Uuid>,
+    pub endpoint_hostname: Option<String>,
+    pub group_id: Option<Uuid>,
+    pub group_name: Option<String>,
+    pub justification: String,
+    pub approved_by: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WaiverRow {
+    pub fn is_active(&self) -> bool {
+        self.expires_at > Utc::now()
+    }
+
+    /// The endpoint or group the waiver covers
+    pub fn scope(&self) -> String {
+        match (&self.endpoint_hostname, &self.group_name) {
+            (Some(hostname), _) => hostname.clone(),
+            (None, Some(group)) => format!("Group: {}", group),
+            (None, None) => "All endpoints".to_string(),
+        }
+    }
+}
+
+pub async fn create_waiver(
+    pool: &PgPool,
+    check_id: Uuid,
+    endpoint_id: Option<Uuid>,
+    group_id: Option<Uuid>,
+    justification: &str,
+    approved_by: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<Uuid, sqlx::Error> {
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO check_waivers (id, check_id, endpoint_id, group_id, justification, approved_by, expires_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        id,
+        check_id,
+        endpoint_id,
+        group_id,
+        justification,
+        approved_by,
+        expires_at,
+        now,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn get_waiver_by_id(pool: &PgPool, id: Uuid) -> Result<Option<WaiverRow>, sqlx::Error> {
+    sqlx::query_as!(
+        WaiverRow,
+        r#"
+        SELECT
+            w.id,
+            w.check_id,
+            cd.name as check_name,
+            w.endpoint_id,
+            e.hostname as "endpoint_hostname?",
+            w.group_id,
+            g.name as "group_name?",
+            w.justification,
+            w.approved_by,
+            w.expires_at,
+            w.created_at
+        FROM check_waivers w
+        JOIN check_definitions cd ON cd.id = w.check_id
+        LEFT JOIN endpoints e ON e.id = w.endpoint_id
+        LEFT JOIN endpoint_groups g ON g.id = w.group_id
+        WHERE w.id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_waivers(pool: &PgPool) -> Result<Vec<WaiverRow>, sqlx::Error> {
+    sqlx::query_as!(
+        WaiverRow,
+        r#"
+        SELECT
+            w.id,
+            w.check_id,
+            cd.name as check_name,
+            w.endpoint_id,
+            e.hostname as "endpoint_hostname?",
+            w.group_id,
+            g.name as "group_name?",
+            w.justification,
+            w.approved_by,
+            w.expires_at,
+            w.created_at
+        FROM check_waivers w
+        JOIN check_definitions cd ON cd.id = w.check_id
+        LEFT JOIN endpoints e ON e.id = w.endpoint_id
+        LEFT JOIN endpoint_groups g ON g.id = w.group_id
+        ORDER BY w.expires_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn delete_waiver(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM check_waivers WHERE id = $1", id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Number of waivers that expired in the half-open interval `(after, until]`
+pub async fn count_waivers_expired_between(
+    pool: &PgPool,
+    after: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as count FROM check_waivers
+        WHERE expires_at > $1 AND expires_at <= $2
+        "#,
+        after,
+        until
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.count.unwrap_or(0))
+}
@@ -8,6 +8,7 @@ use axum::{
     routing::{get, post, put, delete},
     Router,
 };
+use common::signing::{self, SigningKey};
 use sqlx::PgPool;
 use std::sync::Arc;
 use tower_http::trace::TraceLayer;
@@ -19,6 +20,8 @@ use crate::config::Config;
 pub struct AppState {
     pub pool: PgPool,
     pub config: Arc<Config>,
+    /// Key for signing check definitions sent to agents
+    pub signing_key: Option<Arc<SigningKey>>,
 }
 
 #[tokio::main]
@@ -38,6 +41,15 @@ async fn main() -> anyhow::Result<()> {
     let config = Config::from_env().expect("Failed to load configuration");
     let addr = config.socket_addr();
 
+    let signing_key = config.load_signing_key()?;
+    match &signing_key {
+        Some(key) => tracing::info!(
+            "Signing checks; agent CHECK_SIGNING_PUBLIC_KEY={}",
+            signing::encode_verifying_key(&key.verifying_key())
+        ),
+        None => tracing::warn!("CHECK_SIGNING_KEY_FILE not set; checks sent to agents are unsigned"),
+    }
+
     tracing::info!("Connecting to database...");
     let pool = db::create_pool(&config.database_url).await?;
 
@@ -47,10 +59,16 @@ async fn main() -> anyhow::Result<()> {
     let state = AppState {
         pool: pool.clone(),
         config: Arc::new(config.clone()),
+        signing_key: signing_key.map(Arc::new),
     };
 
     // Start background tasks
-    services::start_background_tasks(pool, config.offline_threshold_minutes).await;
+    services::start_background_tasks(
+        pool,
+        config.offline_threshold_minutes,
+        config.check_error_policy,
+    )
+    .await;
 
     // Build router
     let app = Router::new()
@@ -59,15 +77,26 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/agent/heartbeat", post(api::agent::heartbeat))
         .route("/api/agent/checks", get(api::agent::get_checks))
         .route("/api/agent/results", post(api::agent::submit_results))
+        .route("/api/agent/file-changes", post(api::agent::submit_file_changes))
         // Admin API routes
         .route("/api/endpoints", get(api::admin::list_endpoints))
         .route("/api/endpoints/:id", get(api::admin::get_endpoint))
         .route("/api/endpoints/:id", delete(api::admin::delete_endpoint))
+        .route("/api/endpoints/:id/file-changes", get(api::admin::list_file_changes))
+        .route("/api/endpoints/:id/file-changes/accept", post(api::admin::accept_file_changes))
         .route("/api/checks", get(api::admin::list_checks))
         .route("/api/checks", post(api::admin::create_check))
+        .route("/api/checks/schema", get(api::admin::get_check_schema))
         .route("/api/checks/:id", get(api::admin::get_check))
         .route("/api/checks/:id", put(api::admin::update_check))
         .route("/api/checks/:id", delete(api::admin::delete_check))
+        .route("/api/groups", get(api::admin::list_groups))
+        .route("/api/groups", post(api::admin::create_group))
+        .route("/api/groups/:id", delete(api::admin::delete_group))
+        .route("/api/groups/:id/members", put(api::admin::set_group_members))
+        .route("/api/waivers", get(api::admin::list_waivers))
+        .route("/api/waivers", post(api::admin::create_waiver))
+        .route("/api/waivers/:id", delete(api::admin::delete_waiver))
         .route("/api/results", get(api::admin::list_results))
         .route("/api/reports/summary", get(api::admin::get_summary))
         // Web UI routes
@@ -75,12 +104,20 @@ async fn main() -> anyhow::Result<()> {
         .route("/endpoints", get(web::routes::endpoints_list))
         .route("/endpoints/:id", get(web::routes::endpoint_detail))
         .route("/endpoints/:id/delete", post(web::routes::endpoint_delete))
+        .route("/endpoints/:id/file-changes/accept", post(web::routes::file_changes_accept))
         .route("/checks", get(web::routes::checks_list))
         .route("/checks/new", get(web::routes::check_new))
         .route("/checks", post(web::routes::check_create))
         .route("/checks/:id/edit", get(web::routes::check_edit))
         .route("/checks/:id", post(web::routes::check_update))
         .route("/checks/:id/delete", post(web::routes::check_delete))
+        .route("/groups", get(web::routes::groups_list))
+        .route("/groups", post(web::routes::group_create))
+        .route("/groups/:id/members", post(web::routes::group_members_update))
+        .route("/groups/:id/delete", post(web::routes::group_delete))
+        .route("/waivers", get(web::routes::waivers_list))
+        .route("/waivers", post(web::routes::waiver_create))
+        .route("/waivers/:id/delete", post(web::routes::waiver_delete))
         .route("/reports", get(web::routes::reports))
         // Auth routes
         .route("/login", get(web::routes::login_page))
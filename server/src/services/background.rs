@@ -1,16 +1,29 @@
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use std::time::Duration;
 use tokio::time::interval;
 
-use crate::db::{endpoints, snapshots};
+use crate::config::CheckErrorPolicy;
+use crate::db::{endpoints, snapshots, waivers};
+use crate::services::status;
 
-pub async fn start_background_tasks(pool: PgPool, offline_threshold_minutes: i64) {
+pub async fn start_background_tasks(
+    pool: PgPool,
+    offline_threshold_minutes: i64,
+    check_error_policy: CheckErrorPolicy,
+) {
     // Start endpoint status updater
     let pool_clone = pool.clone();
     tokio::spawn(async move {
         endpoint_status_updater(pool_clone, offline_threshold_minutes).await;
     });
 
+    // Recompute endpoint statuses when waivers lapse
+    let pool_clone = pool.clone();
+    tokio::spawn(async move {
+        waiver_expiry_watcher(pool_clone, check_error_policy).await;
+    });
+
     // Start snapshot cleanup (keep 7 days of data)
     tokio::spawn(async move {
         snapshot_cleanup(pool, 7).await;
@@ -36,6 +49,40 @@ async fn endpoint_status_updater(pool: PgPool, threshold_minutes: i64) {
     }
 }
 
+async fn waiver_expiry_watcher(pool: PgPool, check_error_policy: CheckErrorPolicy) {
+    let mut ticker = interval(Duration::from_secs(60));
+    // Waivers may have lapsed while the server was down, so the first pass
+    // recomputes statuses unconditionally
+    let mut last_check: Option<DateTime<Utc>> = None;
+
+    loop {
+        ticker.tick().await;
+
+        let now = Utc::now();
+        if let Some(since) = last_check {
+            match waivers::count_waivers_expired_between(&pool, since, now).await {
+                Ok(0) => {
+                    last_check = Some(now);
+                    continue;
+                }
+                Ok(count) => {
+                    tracing::info!("{} waivers expired, recomputing endpoint statuses", count);
+                }
+                Err(e) => {
+                    tracing::error!("Error checking for expired waivers: {:?}", e);
+                    continue;
+                }
+            }
+        }
+
+        if let Err(e) = status::refresh_endpoint_statuses(&pool, check_error_policy).await {
+            tracing::error!("Error recomputing endpoint statuses: {:?}", e);
+            continue;
+        }
+        last_check = Some(now);
+    }
+}
+
 async fn snapshot_cleanup(pool: PgPool, days_to_keep: i64) {
     let mut ticker = interval(Duration::from_secs(3600)); // Every hour
 
@@ -1,3 +1,4 @@
 pub mod background;
+pub mod status;
 
 pub use background::*;
@@ -0,0 +1,88 @@
+use common::{CheckStatus, EndpointStatus, Severity};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::CheckErrorPolicy;
+use crate::db::{endpoints, results};
+
+/// Derive an endpoint status from the latest state of its checks.
+///
+/// A failing critical or high severity check makes the endpoint critical,
+/// medium and low failures make it a warning. Failing info checks never
+/// change the status. Errors are handled according to `policy`.
+pub fn derive_endpoint_status(
+    states: impl IntoIterator<Item = (CheckStatus, Severity)>,
+    policy: CheckErrorPolicy,
+) -> EndpointStatus {
+    let mut status = EndpointStatus::Online;
+
+    for (check_status, severity) in states {
+        let effective = match (check_status, policy) {
+            (CheckStatus::Fail, _) | (CheckStatus::Error, CheckErrorPolicy::Severity) => {
+                severity_status(severity)
+            }
+            (CheckStatus::Error, CheckErrorPolicy::Warning) => EndpointStatus::Warning,
+            _ => EndpointStatus::Online,
+        };
+
+        if effective == EndpointStatus::Critical {
+            return EndpointStatus::Critical;
+        }
+        if effective == EndpointStatus::Warning {
+            status = EndpointStatus::Warning;
+        }
+    }
+
+    status
+}
+
+fn severity_status(severity: Severity) -> EndpointStatus {
+    match severity {
+        Severity::Critical | Severity::High => EndpointStatus::Critical,
+        Severity::Medium | Severity::Low => EndpointStatus::Warning,
+        Severity::Info => EndpointStatus::Online,
+    }
+}
+
+/// Compute the status of an endpoint from its stored check results
+pub async fn compute_endpoint_status(
+    pool: &PgPool,
+    endpoint_id: Uuid,
+    policy: CheckErrorPolicy,
+) -> Result<EndpointStatus, sqlx::Error> {
+    let states = results::get_latest_check_states_for_endpoint(pool, endpoint_id).await?;
+
+    Ok(derive_endpoint_status(
+        states.into_iter().map(|s| {
+            (
+                s.status.parse().unwrap_or(CheckStatus::Error),
+                s.severity
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(Severity::Medium),
+            )
+        }),
+        policy,
+    ))
+}
+
+/// Recompute the status of every endpoint that is not offline.
+///
+/// Called after check definitions change, since editing, disabling or
+/// deleting a check can change the outcome without any agent reporting.
+pub async fn refresh_endpoint_statuses(
+    pool: &PgPool,
+    policy: CheckErrorPolicy,
+) -> Result<(), sqlx::Error> {
+    for endpoint in endpoints::list_endpoints(pool).await? {
+        if endpoint.status == EndpointStatus::Offline {
+            continue;
+        }
+
+        let status = compute_endpoint_status(pool, endpoint.id, policy).await?;
+        if status != endpoint.status {
+            endpoints::update_endpoint_status(pool, endpoint.id, status).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -3,12 +3,17 @@ use axum::{
     response::{Html, IntoResponse, Redirect, Response},
 };
 use axum_extra::extract::cookie::CookieJar;
-use common::{AdminRole, CheckStatus, Severity};
+use chrono::{NaiveDate, Utc};
+use common::{
+    AdminRole, CheckSchedule, CheckStatus, CheckType, FieldError, FileChangeKind, FileChangeStatus,
+    Severity,
+};
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::AppState;
-use crate::db::{checks, endpoints, results, snapshots, users};
+use crate::db::{checks, endpoints, file_changes, groups, results, snapshots, users, waivers};
+use crate::services::status;
 use crate::web::auth::{
     create_session_cookie, clear_session_cookie, hash_password, verify_password,
     AuthenticatedUser, Session,
@@ -98,6 +103,35 @@ pub async fn endpoint_detail(
         .await
         .unwrap_or_default();
 
+    let supported_check_types = endpoints::get_supported_check_types(&state.pool, id)
+        .await
+        .unwrap_or_default();
+
+    let fim_scan = endpoints::get_fim_scan(&state.pool, id)
+        .await
+        .ok()
+        .flatten()
+        .map(|scan| FimScanView {
+            scanned_at: scan.scanned_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            files_scanned: scan.files_scanned,
+        });
+
+    let file_changes: Vec<FileChangeView> = file_changes::list_changes(&state.pool, id, None, 100)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| FileChangeView {
+            kind: c.change_type.parse().unwrap_or(FileChangeKind::Modified),
+            status: c.status.parse().unwrap_or(FileChangeStatus::Open),
+            baseline: FileChangeView::describe(c.baseline_state().as_ref()),
+            current: FileChangeView::describe(c.current_state().as_ref()),
+            path: c.path,
+            first_seen_at: c.first_seen_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            last_seen_at: c.last_seen_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            resolved_by: c.resolved_by,
+        })
+        .collect();
+
     let check_results: Vec<CheckResultView> = latest_results
         .into_iter()
         .map(|r| CheckResultView {
@@ -105,7 +139,12 @@ pub async fn endpoint_detail(
             check_name: r.check_name,
             status: r.status.parse().unwrap_or(CheckStatus::Error),
             message: r.message,
+            value: r.value,
+            evidence: r
+                .evidence
+                .and_then(|e| serde_json::to_string_pretty(&e).ok()),
             collected_at: r.collected_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            duration_ms: r.duration_ms,
         })
         .collect();
 
@@ -114,6 +153,9 @@ pub async fn endpoint_detail(
         endpoint: EndpointView::from(endpoint),
         snapshot: snapshot.map(SnapshotView::from),
         check_results,
+        supported_check_types,
+        fim_scan,
+        file_changes,
     }
     .into_response()
 }
@@ -127,6 +169,16 @@ pub async fn endpoint_delete(
     Redirect::to("/endpoints")
 }
 
+/// Accept every open file change into the endpoint's baseline
+pub async fn file_changes_accept(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let _ = file_changes::accept_open_changes(&state.pool, id, &user.session.username).await;
+    Redirect::to(&format!("/endpoints/{}", id))
+}
+
 // Checks
 pub async fn checks_list(
     State(state): State<AppState>,
@@ -137,6 +189,10 @@ pub async fn checks_list(
     let checks: Vec<CheckDefView> = check_list
         .into_iter()
         .map(|c| CheckDefView {
+            schedule: c
+                .schedule()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "Every cycle".to_string()),
             id: c.id,
             name: c.name,
             description: c.description.unwrap_or_default(),
@@ -156,8 +212,11 @@ pub async fn checks_list(
 pub async fn check_new(_user: AuthenticatedUser) -> impl IntoResponse {
     CheckFormTemplate {
         title: "New Check".to_string(),
-        check: None,
+        action: "/checks".to_string(),
+        is_new: true,
+        check: CheckFormView::default(),
         parameters_json: "{}".to_string(),
+        errors: Vec::new(),
     }
 }
 
@@ -171,18 +230,23 @@ pub async fn check_edit(
         _ => return Redirect::to("/checks").into_response(),
     };
 
+    let (schedule_type, schedule_value) = CheckFormView::schedule_fields(check.schedule().as_ref());
+
     CheckFormTemplate {
         title: format!("Edit Check: {}", check.name),
-        check: Some(CheckDefView {
-            id: check.id,
+        action: format!("/checks/{}", check.id),
+        is_new: false,
+        check: CheckFormView {
             name: check.name,
             description: check.description.unwrap_or_default(),
             check_type: check.check_type,
             severity: check.severity.parse().unwrap_or(Severity::Medium),
+            schedule_type,
+            schedule_value,
             enabled: check.enabled,
-            updated_at: check.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        }),
+        },
         parameters_json: serde_json::to_string_pretty(&check.parameters).unwrap_or_default(),
+        errors: Vec::new(),
     }
     .into_response()
 }
@@ -195,34 +259,122 @@ pub struct CheckForm {
     pub parameters: String,
     pub severity: String,
     #[serde(default)]
+    pub schedule_type: String,
+    #[serde(default)]
+    pub schedule_value: String,
+    #[serde(default)]
     pub enabled: Option<String>,
 }
 
+impl CheckForm {
+    /// Parse and validate the submitted parameters and schedule.
+    ///
+    /// On failure the form is rendered again with the submitted values and
+    /// the field errors, so nothing the user typed is lost.
+    fn validate(
+        &self,
+        title: String,
+        action: String,
+        is_new: bool,
+    ) -> Result<(CheckType, Option<CheckSchedule>), Box<CheckFormTemplate>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(FieldError::new("name", "must not be empty"));
+        }
+
+        let schedule = match self.schedule() {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                errors.push(FieldError::new("schedule", e));
+                None
+            }
+        };
+
+        let check_type = match serde_json::from_str::<serde_json::Value>(&self.parameters) {
+            Ok(parameters) => match CheckType::from_parts(&self.check_type, &parameters) {
+                Ok(check_type) => Some(check_type),
+                Err(e) => {
+                    errors.extend(e);
+                    None
+                }
+            },
+            Err(e) => {
+                errors.push(FieldError::new("parameters", format!("invalid JSON: {}", e)));
+                None
+            }
+        };
+
+        if let (Some(check_type), true) = (check_type, errors.is_empty()) {
+            return Ok((check_type, schedule));
+        }
+
+        Err(Box::new(CheckFormTemplate {
+            title,
+            action,
+            is_new,
+            check: CheckFormView {
+                name: self.name.clone(),
+                description: self.description.clone(),
+                check_type: self.check_type.clone(),
+                severity: self.severity.parse().unwrap_or(Severity::Medium),
+                schedule_type: self.schedule_type.clone(),
+                schedule_value: self.schedule_value.clone(),
+                enabled: self.enabled.is_some(),
+            },
+            parameters_json: self.parameters.clone(),
+            errors,
+        }))
+    }
+
+    /// Fields to store once `validate` has accepted the form
+    fn fields<'a>(
+        &'a self,
+        check_type: &'a CheckType,
+        schedule: Option<&'a CheckSchedule>,
+    ) -> checks::CheckFields<'a> {
+        checks::CheckFields {
+            name: &self.name,
+            description: Some(self.description.as_str()).filter(|d| !d.is_empty()),
+            check_type,
+            severity: self.severity.parse().unwrap_or(Severity::Medium),
+            schedule,
+            enabled: self.enabled.is_some(),
+        }
+    }
+
+    fn schedule(&self) -> Result<Option<CheckSchedule>, String> {
+        let value = self.schedule_value.trim();
+        let schedule = match self.schedule_type.as_str() {
+            "" => return Ok(None),
+            "interval" => CheckSchedule::Interval {
+                seconds: value
+                    .parse()
+                    .map_err(|_| "interval must be a number of seconds".to_string())?,
+            },
+            "cron" => CheckSchedule::Cron {
+                expression: value.to_string(),
+            },
+            other => return Err(format!("unknown schedule type: {}", other)),
+        };
+
+        schedule.validate()?;
+        Ok(Some(schedule))
+    }
+}
+
 pub async fn check_create(
     State(state): State<AppState>,
     _user: AuthenticatedUser,
     Form(form): Form<CheckForm>,
-) -> impl IntoResponse {
-    let parameters: serde_json::Value = serde_json::from_str(&form.parameters).unwrap_or_default();
-    let severity: Severity = form.severity.parse().unwrap_or(Severity::Medium);
-    let enabled = form.enabled.is_some();
-
-    let _ = checks::create_check(
-        &state.pool,
-        &form.name,
-        if form.description.is_empty() {
-            None
-        } else {
-            Some(&form.description)
-        },
-        &form.check_type,
-        parameters,
-        severity,
-        enabled,
-    )
-    .await;
+) -> Response {
+    let (check_type, schedule) = match form.validate("New Check".to_string(), "/checks".to_string(), true) {
+        Ok(validated) => validated,
+        Err(page) => return (*page).into_response(),
+    };
+    let _ = checks::create_check(&state.pool, &form.fields(&check_type, schedule.as_ref())).await;
 
-    Redirect::to("/checks")
+    Redirect::to("/checks").into_response()
 }
 
 pub async fn check_update(
@@ -230,28 +382,20 @@ pub async fn check_update(
     _user: AuthenticatedUser,
     Path(id): Path<Uuid>,
     Form(form): Form<CheckForm>,
-) -> impl IntoResponse {
-    let parameters: serde_json::Value = serde_json::from_str(&form.parameters).unwrap_or_default();
-    let severity: Severity = form.severity.parse().unwrap_or(Severity::Medium);
-    let enabled = form.enabled.is_some();
+) -> Response {
+    let (check_type, schedule) = match form.validate(
+        format!("Edit Check: {}", form.name),
+        format!("/checks/{}", id),
+        false,
+    ) {
+        Ok(validated) => validated,
+        Err(page) => return (*page).into_response(),
+    };
+    let _ = checks::update_check(&state.pool, id, &form.fields(&check_type, schedule.as_ref())).await;
 
-    let _ = checks::update_check(
-        &state.pool,
-        id,
-        &form.name,
-        if form.description.is_empty() {
-            None
-        } else {
-            Some(&form.description)
-        },
-        &form.check_type,
-        parameters,
-        severity,
-        enabled,
-    )
-    .await;
+    let _ = status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await;
 
-    Redirect::to("/checks")
+    Redirect::to("/checks").into_response()
 }
 
 pub async fn check_delete(
@@ -260,9 +404,278 @@ pub async fn check_delete(
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
     let _ = checks::delete_check(&state.pool, id).await;
+    let _ = status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await;
     Redirect::to("/checks")
 }
 
+// Endpoint groups
+pub async fn groups_list(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> impl IntoResponse {
+    groups_page(&state, None).await
+}
+
+async fn groups_page(state: &AppState, error: Option<String>) -> GroupsTemplate {
+    let group_list = groups::list_groups(&state.pool).await.unwrap_or_default();
+    let endpoint_list = endpoints::list_endpoints(&state.pool).await.unwrap_or_default();
+
+    let groups: Vec<GroupView> = group_list
+        .into_iter()
+        .map(|g| GroupView {
+            members: endpoint_list
+                .iter()
+                .map(|e| MemberOption {
+                    value: e.id,
+                    label: e.hostname.clone(),
+                    selected: g.endpoint_ids.contains(&e.id),
+                })
+                .collect(),
+            hostnames: g.hostnames.join(", "),
+            id: g.id,
+            name: g.name,
+        })
+        .collect();
+
+    GroupsTemplate {
+        title: "Groups".to_string(),
+        groups,
+        endpoints: endpoint_list
+            .into_iter()
+            .map(|e| SelectOption {
+                value: e.id,
+                label: e.hostname,
+            })
+            .collect(),
+        error,
+    }
+}
+
+/// Fields of a group form; a multiple select repeats `endpoint_id` once per
+/// chosen endpoint, so the form is read as raw pairs
+fn group_form_fields(fields: &[(String, String)]) -> Result<(String, Vec<Uuid>), String> {
+    let mut name = String::new();
+    let mut endpoint_ids = Vec::new();
+    for (key, value) in fields {
+        match key.as_str() {
+            "name" => name = value.trim().to_string(),
+            "endpoint_id" => endpoint_ids.push(
+                value
+                    .parse::<Uuid>()
+                    .map_err(|_| "Invalid endpoint".to_string())?,
+            ),
+            _ => {}
+        }
+    }
+    Ok((name, endpoint_ids))
+}
+
+pub async fn group_create(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Response {
+    let (name, endpoint_ids) = match group_form_fields(&fields) {
+        Ok(fields) => fields,
+        Err(e) => return groups_page(&state, Some(e)).await.into_response(),
+    };
+    if name.is_empty() {
+        return groups_page(&state, Some("Name is required".to_string()))
+            .await
+            .into_response();
+    }
+
+    match groups::create_group(&state.pool, &name, &endpoint_ids).await {
+        Ok(_) => Redirect::to("/groups").into_response(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => groups_page(
+            &state,
+            Some(format!("A group named {} already exists", name)),
+        )
+        .await
+        .into_response(),
+        Err(e) => groups_page(&state, Some(format!("Failed to create group: {}", e)))
+            .await
+            .into_response(),
+    }
+}
+
+pub async fn group_members_update(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Response {
+    let endpoint_ids = match group_form_fields(&fields) {
+        Ok((_, endpoint_ids)) => endpoint_ids,
+        Err(e) => return groups_page(&state, Some(e)).await.into_response(),
+    };
+
+    if let Err(e) = groups::set_group_members(&state.pool, id, &endpoint_ids).await {
+        return groups_page(&state, Some(format!("Failed to update group: {}", e)))
+            .await
+            .into_response();
+    }
+    let _ = status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await;
+
+    Redirect::to("/groups").into_response()
+}
+
+pub async fn group_delete(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let _ = groups::delete_group(&state.pool, id).await;
+    let _ = status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await;
+    Redirect::to("/groups")
+}
+
+// Waivers
+pub async fn waivers_list(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+) -> impl IntoResponse {
+    waivers_page(&state, None).await
+}
+
+async fn waivers_page(state: &AppState, error: Option<String>) -> WaiversTemplate {
+    let waiver_list = waivers::list_waivers(&state.pool).await.unwrap_or_default();
+    let check_list = checks::list_checks(&state.pool).await.unwrap_or_default();
+    let endpoint_list = endpoints::list_endpoints(&state.pool).await.unwrap_or_default();
+    let group_list = groups::list_groups(&state.pool).await.unwrap_or_default();
+
+    let waivers: Vec<WaiverView> = waiver_list
+        .into_iter()
+        .map(|w| WaiverView {
+            active: w.is_active(),
+            scope: w.scope(),
+            id: w.id,
+            check_name: w.check_name,
+            justification: w.justification,
+            approved_by: w.approved_by,
+            expires_at: w.expires_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        })
+        .collect();
+
+    WaiversTemplate {
+        title: "Waivers".to_string(),
+        waivers,
+        checks: check_list
+            .into_iter()
+            .map(|c| SelectOption {
+                value: c.id,
+                label: c.name,
+            })
+            .collect(),
+        endpoints: endpoint_list
+            .into_iter()
+            .map(|e| SelectOption {
+                value: e.id,
+                label: e.hostname,
+            })
+            .collect(),
+        groups: group_list
+            .into_iter()
+            .map(|g| SelectOption {
+                value: g.id,
+                label: g.name,
+            })
+            .collect(),
+        error,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WaiverForm {
+    pub check_id: Uuid,
+    /// `endpoint:<id>` or `group:<id>`; empty to waive the check on every endpoint
+    pub scope: String,
+    pub justification: String,
+    pub expires_on: String,
+}
+
+pub async fn waiver_create(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Form(form): Form<WaiverForm>,
+) -> Response {
+    let scope = match form.scope.split_once(':') {
+        None if form.scope.is_empty() => Some((None, None)),
+        Some(("endpoint", id)) => id.parse::<Uuid>().ok().map(|id| (Some(id), None)),
+        Some(("group", id)) => id.parse::<Uuid>().ok().map(|id| (None, Some(id))),
+        _ => None,
+    };
+    let Some((endpoint_id, group_id)) = scope else {
+        return waivers_page(&state, Some("Invalid scope".to_string()))
+            .await
+            .into_response();
+    };
+
+    // Waivers expire at the end of the chosen day (UTC)
+    let expires_at = match NaiveDate::parse_from_str(&form.expires_on, "%Y-%m-%d") {
+        Ok(date) => date.and_hms_opt(23, 59, 59).map(|t| t.and_utc()),
+        Err(_) => None,
+    };
+    let expires_at = match expires_at {
+        Some(t) if t > Utc::now() => t,
+        _ => {
+            return waivers_page(&state, Some("Expiry must be a date in the future".to_string()))
+                .await
+                .into_response()
+        }
+    };
+
+    if form.justification.trim().is_empty() {
+        return waivers_page(&state, Some("Justification is required".to_string()))
+            .await
+            .into_response();
+    }
+
+    match checks::get_check_by_id(&state.pool, form.check_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return waivers_page(&state, Some("Check not found".to_string()))
+                .await
+                .into_response()
+        }
+        Err(e) => {
+            return waivers_page(&state, Some(format!("Failed to create waiver: {}", e)))
+                .await
+                .into_response()
+        }
+    }
+
+    if let Err(e) = waivers::create_waiver(
+        &state.pool,
+        form.check_id,
+        endpoint_id,
+        group_id,
+        form.justification.trim(),
+        &user.session.username,
+        expires_at,
+    )
+    .await
+    {
+        return waivers_page(&state, Some(format!("Failed to create waiver: {}", e)))
+            .await
+            .into_response();
+    }
+
+    let _ = status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await;
+
+    Redirect::to("/waivers").into_response()
+}
+
+pub async fn waiver_delete(
+    State(state): State<AppState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let _ = waivers::delete_waiver(&state.pool, id).await;
+    let _ = status::refresh_endpoint_statuses(&state.pool, state.config.check_error_policy).await;
+    Redirect::to("/waivers")
+}
+
 // Reports
 pub async fn reports(
     State(state): State<AppState>,
@@ -275,14 +688,29 @@ pub async fn reports(
             passed: 0,
             failed: 0,
             errors: 0,
+            waived: 0,
         });
 
+    // Waived results are reported separately and do not count towards the pass rate
+    let assessed = stats.total - stats.waived;
+    let percent = |count: i64| {
+        if assessed > 0 {
+            (count as f64 / assessed as f64 * 100.0) as i64
+        } else {
+            0
+        }
+    };
+
     ReportsTemplate {
         title: "Reports".to_string(),
         total_results: stats.total,
         passed: stats.passed,
         failed: stats.failed,
         errors: stats.errors,
+        waived: stats.waived,
+        pass_pct: percent(stats.passed),
+        fail_pct: percent(stats.failed),
+        error_pct: percent(stats.errors),
     }
 }
 
@@ -1,5 +1,8 @@
 use askama::Template;
-use common::{CheckStatus, Endpoint, EndpointStatus, Severity, SystemSnapshot};
+use common::{
+    CheckSchedule, CheckStatus, Endpoint, EndpointStatus, FieldError, FileChangeKind,
+    FileChangeStatus, FileState, Severity, SystemSnapshot,
+};
 use uuid::Uuid;
 
 #[derive(Template)]
@@ -37,6 +40,7 @@ impl RecentResultView {
             CheckStatus::Fail => "danger",
             CheckStatus::Error => "warning",
             CheckStatus::Skipped => "secondary",
+            CheckStatus::Waived => "info",
         }
     }
 }
@@ -97,6 +101,71 @@ pub struct EndpointDetailTemplate {
     pub endpoint: EndpointView,
     pub snapshot: Option<SnapshotView>,
     pub check_results: Vec<CheckResultView>,
+    pub supported_check_types: Vec<String>,
+    pub fim_scan: Option<FimScanView>,
+    pub file_changes: Vec<FileChangeView>,
+}
+
+impl EndpointDetailTemplate {
+    pub fn has_open_file_changes(&self) -> bool {
+        self.file_changes
+            .iter()
+            .any(|c| c.status == FileChangeStatus::Open)
+    }
+}
+
+pub struct FimScanView {
+    pub scanned_at: String,
+    pub files_scanned: i64,
+}
+
+pub struct FileChangeView {
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub status: FileChangeStatus,
+    pub baseline: String,
+    pub current: String,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+    pub resolved_by: Option<String>,
+}
+
+impl FileChangeView {
+    pub fn kind_class(&self) -> &'static str {
+        match self.kind {
+            FileChangeKind::Added => "info",
+            FileChangeKind::Removed => "danger",
+            FileChangeKind::Modified => "warning",
+        }
+    }
+
+    pub fn status_class(&self) -> &'static str {
+        match self.status {
+            FileChangeStatus::Open => "danger",
+            FileChangeStatus::Accepted => "success",
+            FileChangeStatus::Reverted => "secondary",
+        }
+    }
+
+    /// Short description of a file state for the change feed
+    pub fn describe(state: Option<&FileState>) -> String {
+        let Some(state) = state else {
+            return "-".to_string();
+        };
+
+        let mut parts = vec![match &state.sha256 {
+            Some(hash) => format!("sha256 {}", &hash[..hash.len().min(12)]),
+            None => "unreadable".to_string(),
+        }];
+        parts.push(format!("{} B", state.size));
+        if let Some(mode) = &state.mode {
+            parts.push(mode.clone());
+        }
+        if let (Some(uid), Some(gid)) = (state.uid, state.gid) {
+            parts.push(format!("{}:{}", uid, gid));
+        }
+        parts.join(" ")
+    }
 }
 
 pub struct SnapshotView {
@@ -167,7 +236,11 @@ pub struct CheckResultView {
     pub check_name: String,
     pub status: CheckStatus,
     pub message: Option<String>,
+    pub value: Option<f64>,
+    /// Pretty-printed evidence JSON
+    pub evidence: Option<String>,
     pub collected_at: String,
+    pub duration_ms: Option<i64>,
 }
 
 impl CheckResultView {
@@ -177,6 +250,15 @@ impl CheckResultView {
             CheckStatus::Fail => "danger",
             CheckStatus::Error => "warning",
             CheckStatus::Skipped => "secondary",
+            CheckStatus::Waived => "info",
+        }
+    }
+
+    pub fn duration(&self) -> String {
+        match self.duration_ms {
+            Some(ms) if ms >= 1000 => format!("{:.1} s", ms as f64 / 1000.0),
+            Some(ms) => format!("{} ms", ms),
+            None => "-".to_string(),
         }
     }
 }
@@ -194,6 +276,8 @@ pub struct CheckDefView {
     pub description: String,
     pub check_type: String,
     pub severity: Severity,
+    /// Human readable schedule, "Every cycle" when the check has none
+    pub schedule: String,
     pub enabled: bool,
     pub updated_at: String,
 }
@@ -214,8 +298,122 @@ impl CheckDefView {
 #[template(path = "check_form.html")]
 pub struct CheckFormTemplate {
     pub title: String,
-    pub check: Option<CheckDefView>,
+    /// Form target, `/checks` for new checks or `/checks/{id}` when editing
+    pub action: String,
+    pub is_new: bool,
+    pub check: CheckFormView,
     pub parameters_json: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl CheckFormTemplate {
+    /// JSON Schema of all check types, used for the parameter help text
+    pub fn parameters_schema(&self) -> String {
+        serde_json::to_string(&common::check_schema()).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Errors for a form field, including nested fields such as `parameters.path`
+    pub fn field_errors(&self, field: &str) -> Vec<String> {
+        self.errors
+            .iter()
+            .filter(|e| {
+                e.field == field
+                    || e.field
+                        .strip_prefix(field)
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .map(|e| match e.field.strip_prefix("parameters.") {
+                Some(param) => format!("{}: {}", param, e.message),
+                None => e.message.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Values shown in the check form, either loaded from the database or as submitted
+pub struct CheckFormView {
+    pub name: String,
+    pub description: String,
+    pub check_type: String,
+    pub severity: Severity,
+    /// Empty for every cycle, otherwise `interval` or `cron`
+    pub schedule_type: String,
+    /// Interval in seconds or cron expression, depending on `schedule_type`
+    pub schedule_value: String,
+    pub enabled: bool,
+}
+
+impl CheckFormView {
+    /// Form fields for a stored schedule
+    pub fn schedule_fields(schedule: Option<&CheckSchedule>) -> (String, String) {
+        match schedule {
+            Some(CheckSchedule::Interval { seconds }) => ("interval".to_string(), seconds.to_string()),
+            Some(CheckSchedule::Cron { expression }) => ("cron".to_string(), expression.clone()),
+            None => (String::new(), String::new()),
+        }
+    }
+}
+
+impl Default for CheckFormView {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            check_type: String::new(),
+            severity: Severity::Medium,
+            schedule_type: String::new(),
+            schedule_value: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "waivers.html")]
+pub struct WaiversTemplate {
+    pub title: String,
+    pub waivers: Vec<WaiverView>,
+    pub checks: Vec<SelectOption>,
+    pub endpoints: Vec<SelectOption>,
+    pub groups: Vec<SelectOption>,
+    pub error: Option<String>,
+}
+
+pub struct WaiverView {
+    pub id: Uuid,
+    pub check_name: String,
+    pub scope: String,
+    pub justification: String,
+    pub approved_by: String,
+    pub expires_at: String,
+    pub active: bool,
+}
+
+pub struct SelectOption {
+    pub value: Uuid,
+    pub label: String,
+}
+
+#[derive(Template)]
+#[template(path = "groups.html")]
+pub struct GroupsTemplate {
+    pub title: String,
+    pub groups: Vec<GroupView>,
+    pub endpoints: Vec<SelectOption>,
+    pub error: Option<String>,
+}
+
+pub struct GroupView {
+    pub id: Uuid,
+    pub name: String,
+    pub hostnames: String,
+    pub members: Vec<MemberOption>,
+}
+
+pub struct MemberOption {
+    pub value: Uuid,
+    pub label: String,
+    pub selected: bool,
 }
 
 #[derive(Template)]
@@ -226,6 +424,11 @@ pub struct ReportsTemplate {
     pub passed: i64,
     pub failed: i64,
     pub errors: i64,
+    pub waived: i64,
+    /// Shares of the results not covered by a waiver
+    pub pass_pct: i64,
+    pub fail_pct: i64,
+    pub error_pct: i64,
 }
 
 #[derive(Template)]